@@ -1,3 +1,27 @@
+use std::path::PathBuf;
+
+#[path = "src/sha256.rs"]
+mod sha256;
+
+/// Embed the SHA-256 of the bundled sidecar directory (see
+/// `sha256::dir_hex_digest`) as TABLERREUR_SIDECAR_SHA256 so that the app can
+/// detect a replaced launcher or `_internal` file at runtime.
+/// An explicit TABLERREUR_SIDECAR_SHA256 in the environment takes precedence;
+/// without either, the build simply skips verification.
+fn embed_sidecar_hash() {
+    println!("cargo:rerun-if-env-changed=TABLERREUR_SIDECAR_SHA256");
+    if std::env::var_os("TABLERREUR_SIDECAR_SHA256").is_some() {
+        return;
+    }
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let dir = PathBuf::from("binaries").join(format!("tablerreur-backend-{target}"));
+    println!("cargo:rerun-if-changed={}", dir.display());
+    if let Ok(digest) = sha256::dir_hex_digest(&dir) {
+        println!("cargo:rustc-env=TABLERREUR_SIDECAR_SHA256={digest}");
+    }
+}
+
 fn main() {
+    embed_sidecar_hash();
     tauri_build::build()
 }
@@ -0,0 +1,69 @@
+// ---------------------------------------------------------------------------
+// Aide > À propos: app, backend and platform versions
+// ---------------------------------------------------------------------------
+//
+// Native message boxes cannot always be selected, so the dialog has a Copier
+// button that puts the same text on the clipboard, ready for a support ticket.
+
+use std::env::consts::{ARCH, OS};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::{backend, http, i18n};
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Version reported by `GET /version`: `{"version": …}` or plain text.
+fn backend_version<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let port = backend::current_port(app)?;
+    let resp = http::get(port, "/version", VERSION_TIMEOUT).ok().filter(|r| r.status == 200)?;
+    let text = resp.text();
+    let version = match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(value) => value.get("version")?.as_str()?.to_string(),
+        Err(_) => text.trim().to_string(),
+    };
+    (!version.is_empty()).then_some(version)
+}
+
+fn about_text<R: Runtime>(app: &AppHandle<R>) -> String {
+    let text = i18n::strings();
+    format!(
+        "Tablerreur {}\nbackend : {}\n{} : {OS} {ARCH}\nTauri {}",
+        app.package_info().version,
+        backend_version(app).unwrap_or_else(|| text.unknown.to_string()),
+        text.diag_system,
+        tauri::VERSION
+    )
+}
+
+/// Write *text* to the clipboard through the main webview.
+fn copy<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let text = serde_json::to_string(text).unwrap_or_default();
+    let _ = window.eval(format!("navigator.clipboard && navigator.clipboard.writeText({text});"));
+}
+
+/// Show the À propos dialog. Blocking on the version request: call it off
+/// the UI thread.
+pub fn show<R: Runtime>(app: &AppHandle<R>) {
+    let strings = i18n::strings();
+    let text = about_text(app);
+    let handle = app.clone();
+    app.dialog()
+        .message(text.clone())
+        .title(strings.menu_about)
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            strings.copy.to_string(),
+            strings.close.to_string(),
+        ))
+        .show(move |copy_requested| {
+            if copy_requested {
+                copy(&handle, &text);
+            }
+        });
+}
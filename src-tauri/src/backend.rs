@@ -0,0 +1,857 @@
+// ---------------------------------------------------------------------------
+// Backend process: port selection, spawn, teardown and restart
+// ---------------------------------------------------------------------------
+
+use std::env::consts::{ARCH, OS};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::{
+    deep_link, detached, diagnostics, error_page, health, http, i18n, integrity, logs, mock_backend, ports,
+    settings, timings,
+};
+
+/// Default port range scanned for the backend, as [min, max).
+const DEFAULT_PORT_MIN: u16 = 8400;
+const DEFAULT_PORT_MAX: u16 = 8500;
+
+/// Default time the backend gets to answer its first health probe.
+const DEFAULT_HEALTH_TIMEOUT_SECS: u64 = 90;
+
+/// How long the backend gets to exit on its own after `POST /shutdown`.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// How long a backend started with `--port 0` gets to announce its port.
+const ANNOUNCE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delays before each new launch attempt when `spawn()` itself fails.
+const SPAWN_RETRY_DELAYS: [Duration; 3] =
+    [Duration::from_millis(500), Duration::from_secs(1), Duration::from_secs(2)];
+
+/// How long a live rebind gets before falling back to a full restart.
+const REBIND_TIMEOUT: Duration = Duration::from_secs(15);
+
+// ---------------------------------------------------------------------------
+// Startup limits, overridable from the environment
+// ---------------------------------------------------------------------------
+
+/// Port range and startup health timeout in effect for this run.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Limits {
+    /// Scanned range, as [port_min, port_max).
+    pub port_min: u16,
+    pub port_max: u16,
+    pub health_timeout_secs: u64,
+    /// `TABLERREUR_AUTO_PORT`: start the backend with `--port 0` and read the
+    /// port it announces instead of scanning the range.
+    pub auto_port: bool,
+}
+
+impl Limits {
+    pub fn health_timeout(&self) -> Duration {
+        Duration::from_secs(self.health_timeout_secs)
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        (self.port_min..self.port_max).contains(&port)
+    }
+}
+
+/// Overrides rejected by `read_limits`, kept for `log_limit_notes`: the
+/// limits can be read before the log is set up.
+static LIMIT_NOTES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn limit_note(note: String) {
+    if let Ok(mut notes) = LIMIT_NOTES.lock() {
+        notes.push(note);
+    }
+}
+
+/// Read the limits and write the overrides they rejected, if any, to the log.
+pub fn log_limit_notes<R: Runtime>(app: &impl Manager<R>) {
+    limits();
+    let notes = LIMIT_NOTES.lock().map(|mut notes| std::mem::take(&mut *notes)).unwrap_or_default();
+    for note in notes {
+        logs::note(app, &note);
+    }
+}
+
+/// Value of the environment variable *name*, if set and accepted by *valid*.
+/// A rejected value is noted and ignored.
+fn env_override<T: std::str::FromStr>(name: &str, valid: impl Fn(&T) -> bool) -> Option<T> {
+    let raw = std::env::var(name).ok()?;
+    let parsed = raw.trim().parse::<T>().ok().filter(|value| valid(value));
+    if parsed.is_none() {
+        limit_note(format!("{name}={raw} ignoré : valeur invalide"));
+    }
+    parsed
+}
+
+fn read_limits() -> Limits {
+    let port_min = env_override("TABLERREUR_PORT_MIN", |&p: &u16| p > 0).unwrap_or(DEFAULT_PORT_MIN);
+    let port_max = env_override("TABLERREUR_PORT_MAX", |&p: &u16| p > 0).unwrap_or(DEFAULT_PORT_MAX);
+    let (port_min, port_max) = if port_min < port_max {
+        (port_min, port_max)
+    } else {
+        limit_note(format!("plage de ports vide ({port_min}–{port_max}), valeurs par défaut utilisées"));
+        (DEFAULT_PORT_MIN, DEFAULT_PORT_MAX)
+    };
+    let health_timeout_secs = env_override("TABLERREUR_HEALTH_TIMEOUT_SECS", |&s: &u64| s > 0)
+        .unwrap_or(DEFAULT_HEALTH_TIMEOUT_SECS);
+    let auto_port = std::env::var("TABLERREUR_AUTO_PORT").is_ok_and(|v| !v.is_empty() && v != "0");
+    Limits { port_min, port_max, health_timeout_secs, auto_port }
+}
+
+/// Limits in effect, read once from `TABLERREUR_PORT_MIN`,
+/// `TABLERREUR_PORT_MAX` and `TABLERREUR_HEALTH_TIMEOUT_SECS`, falling back
+/// to 8400–8500 and 90 s when absent or invalid.
+pub fn limits() -> Limits {
+    static LIMITS: OnceLock<Limits> = OnceLock::new();
+    *LIMITS.get_or_init(read_limits)
+}
+
+/// Effective limits, as shown in the startup diagnostics.
+pub fn limits_diag() -> String {
+    let limits = limits();
+    let text = i18n::strings();
+    let ports = if limits.auto_port {
+        format!("{} : {}", text.diag_port, text.diag_auto_port)
+    } else {
+        format!("{} : {}–{}", text.diag_port_range, limits.port_min, limits.port_max)
+    };
+    format!("{ports}\n{} : {} {}", text.diag_timeout, limits.health_timeout_secs, text.diag_seconds)
+}
+
+// ---------------------------------------------------------------------------
+// Managed state: keeps the sidecar child alive for the app lifetime
+// ---------------------------------------------------------------------------
+
+pub struct SidecarState(pub Mutex<Option<Child>>);
+
+/// Runtime facts about the backend shared between the startup thread, the
+/// health monitor and the menu handlers.
+pub struct BackendState {
+    /// Port of the healthy backend, `None` while starting or restarting.
+    pub port: Mutex<Option<u16>>,
+    /// Set for the duration of a restart so that restarts never overlap.
+    pub restarting: AtomicBool,
+    /// Automatic respawns left for this session, so a crash-looping backend
+    /// cannot spin forever. Drawn on by every spawn the app makes on its own:
+    /// launch retries, health-monitor recovery and the scheduled restart. The
+    /// first launch and restarts asked for by the user are not counted.
+    pub restarts_left: AtomicU32,
+}
+
+impl BackendState {
+    pub fn new(restart_budget: u32) -> Self {
+        Self {
+            port: Mutex::new(None),
+            restarting: AtomicBool::new(false),
+            restarts_left: AtomicU32::new(restart_budget),
+        }
+    }
+}
+
+/// Port of the healthy backend, if any.
+pub fn current_port<R: Runtime>(app: &impl Manager<R>) -> Option<u16> {
+    app.try_state::<BackendState>()
+        .and_then(|state| state.port.lock().ok().and_then(|port| *port))
+}
+
+fn set_port<R: Runtime>(app: &impl Manager<R>, port: Option<u16>) {
+    if let Some(state) = app.try_state::<BackendState>() {
+        if let Ok(mut guard) = state.port.lock() {
+            *guard = port;
+        }
+    }
+}
+
+/// Consume one automatic restart from the session budget; false when exhausted.
+pub fn take_restart_token<R: Runtime>(app: &impl Manager<R>) -> bool {
+    app.try_state::<BackendState>().is_some_and(|state| {
+        state
+            .restarts_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    })
+}
+
+pub fn is_restarting<R: Runtime>(app: &impl Manager<R>) -> bool {
+    app.try_state::<BackendState>()
+        .is_some_and(|state| state.restarting.load(Ordering::SeqCst))
+}
+
+// ---------------------------------------------------------------------------
+// Process helpers
+// ---------------------------------------------------------------------------
+
+/// Why the backend could not be launched.
+#[derive(Debug)]
+pub enum SpawnError {
+    /// The executable (bundled sidecar, or Python in debug builds) is missing.
+    Missing(String),
+    /// It exists but could not be started.
+    Failed(String),
+}
+
+impl From<SpawnError> for String {
+    fn from(e: SpawnError) -> Self {
+        match e {
+            SpawnError::Missing(diag) | SpawnError::Failed(diag) => diag,
+        }
+    }
+}
+
+/// Bind the first free TCP port in [start, end) and keep it bound, or None if
+/// all are taken. Holding the listener until the backend is spawned keeps
+/// other processes off the port in the meantime.
+pub fn reserve_free_port(start: u16, end: u16) -> Option<TcpListener> {
+    (start..end).find_map(|port| TcpListener::bind(("127.0.0.1", port)).ok())
+}
+
+/// Port held by a reservation from `reserve_free_port`.
+pub fn reserved_port(reservation: &TcpListener) -> Result<u16, String> {
+    reservation.local_addr().map(|addr| addr.port()).map_err(|e| e.to_string())
+}
+
+/// Port the backend is to be started on.
+pub enum Bind {
+    /// A port from the range, held until the spawn.
+    Reserved(TcpListener),
+    /// `--port 0`: the backend binds whatever the OS gives it and announces
+    /// it on stdout.
+    Auto,
+}
+
+/// Port of a freshly spawned backend: known upfront, or still to be read
+/// from its output.
+pub enum SpawnedPort {
+    Known(u16),
+    Announced(mpsc::Receiver<u16>),
+}
+
+impl SpawnedPort {
+    /// The port, blocking up to ANNOUNCE_TIMEOUT for an announced one.
+    pub fn wait(self) -> Result<u16, String> {
+        match self {
+            SpawnedPort::Known(port) => Ok(port),
+            SpawnedPort::Announced(rx) => rx.recv_timeout(ANNOUNCE_TIMEOUT).map_err(|_| {
+                format!(
+                    "Le serveur n'a pas annoncé son port en {} secondes \
+                     (ligne « LISTENING ON 127.0.0.1:<port> » attendue).",
+                    ANNOUNCE_TIMEOUT.as_secs()
+                )
+            }),
+        }
+    }
+}
+
+/// How the next backend gets its port, according to `auto_port`.
+pub fn next_bind() -> Result<Bind, String> {
+    if limits().auto_port {
+        Ok(Bind::Auto)
+    } else {
+        free_port().map(Bind::Reserved)
+    }
+}
+
+/// Launch the backend: `python -m` from the repository in debug builds, the
+/// bundled PyInstaller sidecar in release builds.
+///
+/// A reserved port is released right before `spawn()`. This narrows the race
+/// on the port without closing it: the child only binds once its interpreter
+/// is up, and another process could still take the port in that window.
+/// `Bind::Auto` closes it, for backends that announce the port they bound.
+pub fn spawn_sidecar<R: Runtime>(
+    app: &AppHandle<R>,
+    bind: Bind,
+) -> Result<(Child, SpawnedPort), SpawnError> {
+    let port = match &bind {
+        Bind::Reserved(reservation) => reserved_port(reservation).map_err(SpawnError::Failed)?,
+        Bind::Auto => 0,
+    };
+    let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("CARGO_MANIFEST_DIR doit pointer vers src-tauri")
+        .to_path_buf();
+
+    let (mut command, describe) = if cfg!(debug_assertions) {
+        let py = std::env::var("TABLERREUR_PYTHON").unwrap_or_else(|_| {
+            if cfg!(target_os = "windows") {
+                "python".to_string()
+            } else {
+                "python3".to_string()
+            }
+        });
+        let mut command = std::process::Command::new(&py);
+        command
+            .current_dir(&repo_root)
+            .args(["-m", "spreadsheet_qa.web", "--port", &port.to_string()]);
+        let describe = move |e: std::io::Error| {
+            format!(
+                "Impossible de lancer le backend Python ({py}) depuis {} : {e}\n\
+                 Définissez TABLERREUR_PYTHON ou installez : pip install -e \".[web]\"",
+                repo_root.display()
+            )
+        };
+        (command, Box::new(describe) as Box<dyn Fn(std::io::Error) -> String>)
+    } else {
+        let exe_path = sidecar_path(app).map_err(SpawnError::Missing)?;
+        if !exe_path.exists() {
+            return Err(SpawnError::Missing(format!(
+                "Sidecar introuvable : {}\n\
+                 Exécutez d'abord : python scripts/build_sidecar.py",
+                exe_path.display()
+            )));
+        }
+        let mut command = std::process::Command::new(&exe_path);
+        command.args(["--port", &port.to_string()]);
+        let describe = |e: std::io::Error| format!("Impossible de lancer le sidecar : {e}");
+        (command, Box::new(describe) as Box<dyn Fn(std::io::Error) -> String>)
+    };
+    command.stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped());
+    drop(bind);
+    let mut child = spawn_with_retry(app, &mut command, describe)?;
+    if port != 0 {
+        logs::capture(app, &mut child, None);
+        return Ok((child, SpawnedPort::Known(port)));
+    }
+    let (tx, rx) = mpsc::channel();
+    logs::capture(app, &mut child, Some(tx));
+    Ok((child, SpawnedPort::Announced(rx)))
+}
+
+/// Launch *command*, retrying after each delay of `SPAWN_RETRY_DELAYS` when
+/// the launch itself fails: on a cold machine an antivirus scan or a slow
+/// disk can hold the executable for a moment. Each retry draws on the
+/// restart budget and the loop stops once it is exhausted. A missing
+/// executable is reported at once. Each attempt is logged and traced.
+fn spawn_with_retry<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &mut std::process::Command,
+    describe: Box<dyn Fn(std::io::Error) -> String>,
+) -> Result<Child, SpawnError> {
+    let mut delays = SPAWN_RETRY_DELAYS.iter();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let e = match command.spawn() {
+            Ok(child) => {
+                timings::trace(app, "spawn_attempt", serde_json::json!({ "attempt": attempt, "ok": true }));
+                return Ok(child);
+            }
+            Err(e) => e,
+        };
+        timings::trace(
+            app,
+            "spawn_attempt",
+            serde_json::json!({ "attempt": attempt, "ok": false, "error": e.to_string() }),
+        );
+        if e.kind() == std::io::ErrorKind::NotFound {
+            return Err(SpawnError::Missing(describe(e)));
+        }
+        let Some(delay) = delays.next().filter(|_| take_restart_token(app)) else {
+            return Err(SpawnError::Failed(format!("{}\n(après {attempt} tentatives)", describe(e))));
+        };
+        logs::note(
+            app,
+            &format!("lancement du serveur échoué (tentative {attempt}) : {e}, nouvel essai dans {delay:?}"),
+        );
+        std::thread::sleep(*delay);
+    }
+}
+
+/// Location of the bundled PyInstaller executable (onedir layout).
+pub fn sidecar_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let exe_name = if cfg!(target_os = "windows") {
+        "tablerreur-backend.exe"
+    } else {
+        "tablerreur-backend"
+    };
+    let arch = if cfg!(target_arch = "aarch64") { "aarch64" } else { "x86_64" };
+    let os_part = if cfg!(target_os = "macos") { "apple-darwin" }
+        else if cfg!(target_os = "linux") { "unknown-linux-gnu" }
+        else { "pc-windows-msvc" };
+    let triple = format!("{arch}-{os_part}");
+    Ok(app
+        .path()
+        .resource_dir()
+        .map_err(|e| format!("Impossible de résoudre resource_dir : {e}"))?
+        .join("binaries")
+        .join(format!("tablerreur-backend-{triple}"))
+        .join(exe_name))
+}
+
+/// Termine sans délai le processus sidecar Python (en dernier recours après
+/// `shutdown_sidecar`, ou quand il ne répond plus), y compris un serveur
+/// détaché adopté au lancement.
+pub fn kill_sidecar<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(state) = app.try_state::<SidecarState>() {
+        if let Ok(mut guard) = state.0.lock() {
+            if let Some(mut child) = guard.take() {
+                let _ = child.kill();
+                let _ = child.wait(); // éviter processus zombie
+                remove_pid_file(app);
+            }
+        }
+    }
+    detached::kill_adopted(app);
+}
+
+/// Whether the backend that was on *port* is gone: the child has exited, or,
+/// for an adopted backend without a `Child`, nothing accepts on the port.
+fn has_exited<R: Runtime>(app: &impl Manager<R>, port: u16) -> bool {
+    let child_exited = app.try_state::<SidecarState>().and_then(|state| {
+        let mut guard = state.0.lock().ok()?;
+        let child = guard.as_mut()?;
+        Some(child.try_wait().ok().flatten().is_some())
+    });
+    child_exited.unwrap_or_else(|| http::connect(port, Duration::from_millis(100)).is_err())
+}
+
+/// Exit and restart path: ask the backend to stop through `POST /shutdown`,
+/// so it can close its SQLite handles and remove its temp files, and give it
+/// up to SHUTDOWN_GRACE to exit before killing whatever is left. Returns as
+/// soon as the backend is gone.
+pub fn shutdown_sidecar<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(port) = current_port(app) {
+        // Cleared first so the exit is not reported as a crash.
+        set_port(app, None);
+        let asked = http::post(port, "/shutdown", b"{}", Duration::from_secs(1))
+            .is_ok_and(|resp| (200..300).contains(&resp.status));
+        if asked {
+            let deadline = std::time::Instant::now() + SHUTDOWN_GRACE;
+            while !has_exited(app, port) && std::time::Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+    kill_sidecar(app);
+}
+
+pub fn store_child<R: Runtime>(app: &impl Manager<R>, child: Child) {
+    write_pid_file(app, child.id());
+    if let Some(state) = app.try_state::<SidecarState>() {
+        if let Ok(mut guard) = state.0.lock() {
+            *guard = Some(child);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PID file: finding the backend a crashed launch left behind
+// ---------------------------------------------------------------------------
+//
+// `backend.pid` holds the PID of the live child and is removed whenever the
+// child is stopped on purpose. Finding it at startup means the previous
+// launch died without stopping its backend.
+
+const PID_FILE: &str = "backend.pid";
+
+fn pid_file_path<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PID_FILE))
+}
+
+fn write_pid_file<R: Runtime>(app: &impl Manager<R>, pid: u32) {
+    let Some(path) = pid_file_path(app) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(path, pid.to_string());
+}
+
+/// Forget the recorded PID: the child was stopped or handed over on purpose.
+pub fn remove_pid_file<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(path) = pid_file_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Terminate the backend recorded by a launch that crashed. A PID is only
+/// trusted while it still listens in the backend range, or on a loopback port
+/// under a backend process name (auto-port mode): PIDs are recycled.
+/// Without a PID file nothing is spawned; otherwise the check runs `ss`,
+/// `lsof` or `netstat`: call it off the UI thread.
+pub fn kill_orphan<R: Runtime>(app: &impl Manager<R>) {
+    let Some(pid) = pid_file_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| text.trim().parse::<u32>().ok())
+    else {
+        return;
+    };
+    if ports::holds_backend_port(pid) || ports::holds_loopback_backend_port(pid) {
+        logs::note(app, &format!("serveur orphelin {pid} d'un lancement précédent, arrêt"));
+        let _ = ports::terminate(pid);
+    }
+    remove_pid_file(app);
+}
+
+// ---------------------------------------------------------------------------
+// Navigation and restart
+// ---------------------------------------------------------------------------
+
+/// Record *port* as the live backend and point the main window at it, or at
+/// the report of a deep link received in the meantime.
+pub fn mark_ready<R: Runtime>(app: &AppHandle<R>, port: u16) {
+    set_port(app, Some(port));
+    if let Some(window) = app.get_webview_window("main") {
+        let path = deep_link::take_pending(app).unwrap_or_default();
+        let url_str = format!("http://127.0.0.1:{port}{path}");
+        if let Ok(url) = url_str.parse::<tauri::Url>() {
+            let _ = window.navigate(url);
+            diagnostics::await_frontend(app);
+        }
+    }
+}
+
+/// Fichier > Recharger: reload the backend page in the main window, or bring
+/// the window back to it from wherever it is. A no-op while no backend port
+/// is known, rather than navigating to a dead URL.
+pub fn reload<R: Runtime>(app: &AppHandle<R>) {
+    let (Some(port), Some(window)) = (current_port(app), app.get_webview_window("main")) else {
+        return;
+    };
+    let on_backend = window
+        .url()
+        .is_ok_and(|url| url.host_str() == Some("127.0.0.1") && url.port() == Some(port));
+    if on_backend {
+        let _ = window.eval("location.reload();");
+    } else if let Ok(url) = format!("http://127.0.0.1:{port}").parse::<tauri::Url>() {
+        let _ = window.navigate(url);
+    }
+}
+
+/// Run *f* with the `restarting` flag held, so that the health monitor and
+/// other restart paths stay out of the way until it returns.
+fn exclusive<R: Runtime, T>(
+    app: &AppHandle<R>,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<BackendState>();
+    if state.restarting.swap(true, Ordering::SeqCst) {
+        return Err("Un redémarrage du serveur est déjà en cours".to_string());
+    }
+    let result = f();
+    state.restarting.store(false, Ordering::SeqCst);
+    result
+}
+
+fn free_port() -> Result<TcpListener, String> {
+    let Limits { port_min, port_max, .. } = limits();
+    reserve_free_port(port_min, port_max)
+        .ok_or_else(|| format!("Aucun port libre trouvé entre {port_min} et {port_max}"))
+}
+
+fn ensure_integrity<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    integrity::ensure(app).map_err(|diag| {
+        format!("Le serveur embarqué a été modifié, il n'a pas été relancé.\n{diag}")
+    })
+}
+
+fn health_timeout_diag(port: u16) -> String {
+    let text = i18n::strings();
+    format!(
+        "{}\n{} : {port}\n{}\n{} : {OS} {ARCH}",
+        text.health_timeout,
+        text.diag_port,
+        limits_diag(),
+        text.diag_system
+    )
+}
+
+/// How long a child that closed its stdout gets to actually exit.
+const EXIT_WAIT: Duration = Duration::from_secs(2);
+
+fn exit_code(status: std::process::ExitStatus) -> String {
+    match status.code() {
+        Some(code) => code.to_string(),
+        None => status.to_string(),
+    }
+}
+
+/// Called by the stdout drain of the child *pid* at end of stream. If that
+/// child is still the live backend (not killed or swapped out on purpose) and
+/// has exited, the main window gets an error page with its exit code.
+pub fn on_output_closed<R: Runtime>(app: &AppHandle<R>, pid: u32) {
+    let deadline = std::time::Instant::now() + EXIT_WAIT;
+    let status = loop {
+        let Some(state) = app.try_state::<SidecarState>() else {
+            return;
+        };
+        let Ok(mut guard) = state.0.lock() else {
+            return;
+        };
+        let Some(child) = guard.as_mut().filter(|child| child.id() == pid) else {
+            return;
+        };
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                guard.take();
+                remove_pid_file(app);
+                break status;
+            }
+            Ok(None) if std::time::Instant::now() < deadline => {}
+            // Still running without an output: leave it to the health monitor.
+            Ok(None) | Err(_) => return,
+        }
+        drop(guard);
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    if is_restarting(app) {
+        return;
+    }
+    let Some(port) = current_port(app) else {
+        return;
+    };
+    set_port(app, None);
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let on_backend = window
+        .url()
+        .is_ok_and(|url| url.host_str() == Some("127.0.0.1") && url.port() == Some(port));
+    if !on_backend {
+        return;
+    }
+    let text = i18n::strings();
+    let log = logs::log_file_path(app)
+        .map_or_else(|| text.diag_unavailable.to_string(), |p| p.display().to_string());
+    error_page::show(
+        &window,
+        text.backend_stopped_title,
+        text.backend_stopped,
+        &format!(
+            "{} : {}\n{} : {port}\n{} : {log}\n{} : {OS} {ARCH}",
+            text.diag_exit_code,
+            exit_code(status),
+            text.diag_port,
+            text.diag_server_log,
+            text.diag_system
+        ),
+    );
+}
+
+/// Stop the current backend through `shutdown_sidecar`, respawn it on a fresh
+/// port and navigate the main window once it is healthy. Returns the new port.
+pub fn restart_backend<R: Runtime>(app: &AppHandle<R>) -> Result<u16, String> {
+    if mock_backend::enabled() {
+        // Nothing to respawn: the mock keeps serving, just reload the window.
+        let port = current_port(app).ok_or("Serveur simulé non démarré")?;
+        mark_ready(app, port);
+        return Ok(port);
+    }
+    exclusive(app, || {
+        shutdown_sidecar(app);
+        let bind = next_bind()?;
+        ensure_integrity(app)?;
+        let (child, port) = spawn_sidecar(app, bind)?;
+        store_child(app, child);
+        let port = port.wait().inspect_err(|_| kill_sidecar(app))?;
+        if !health::wait_for_health(app, port, limits().health_timeout()) {
+            return Err(health_timeout_diag(port));
+        }
+        mark_ready(app, port);
+        Ok(port)
+    })
+}
+
+/// Point every window showing the backend on *old_port* at *new_port*,
+/// keeping the current path so the user stays where they were.
+fn renavigate_windows<R: Runtime>(app: &AppHandle<R>, old_port: Option<u16>, new_port: u16) {
+    for window in app.webview_windows().values() {
+        let Ok(mut url) = window.url() else {
+            continue;
+        };
+        let on_backend = url.host_str() == Some("127.0.0.1") && url.port() == old_port;
+        if on_backend && url.set_port(Some(new_port)).is_ok() {
+            let _ = window.navigate(url);
+        }
+    }
+    diagnostics::await_frontend(app);
+}
+
+/// Start a second backend (whatever sidecar is now on disk) on a new port,
+/// move every window over once it is healthy, then retire the old one.
+/// If the new backend fails its health check it is killed and the old one
+/// keeps serving. Returns the new port.
+pub fn hot_swap<R: Runtime>(app: &AppHandle<R>) -> Result<u16, String> {
+    if mock_backend::enabled() {
+        return Err("Serveur simulé : aucun backend à remplacer".to_string());
+    }
+    exclusive(app, || {
+        ensure_integrity(app)?;
+        let (mut candidate, port) = spawn_sidecar(app, next_bind()?)?;
+        let healthy = port
+            .wait()
+            .and_then(|port| {
+                if health::wait_for_health(app, port, limits().health_timeout()) {
+                    Ok(port)
+                } else {
+                    Err(health_timeout_diag(port))
+                }
+            });
+        let port = match healthy {
+            Ok(port) => port,
+            Err(diag) => {
+                let _ = candidate.kill();
+                let _ = candidate.wait();
+                return Err(format!("Nouveau serveur abandonné, l'ancien reste actif.\n{diag}"));
+            }
+        };
+
+        let old_port = current_port(app);
+        write_pid_file(app, candidate.id());
+        let old_child = app
+            .state::<SidecarState>()
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .replace(candidate);
+        set_port(app, Some(port));
+        renavigate_windows(app, old_port, port);
+
+        // Give the windows a moment to leave the old backend before stopping it.
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(2));
+            if let Some(mut old) = old_child {
+                retire(&mut old, old_port);
+            }
+            detached::kill_adopted(&app);
+        });
+        Ok(port)
+    })
+}
+
+/// Stop a swapped-out *child* serving on *port* the way `shutdown_sidecar`
+/// stops the live one: `POST /shutdown`, up to SHUTDOWN_GRACE to exit, then
+/// a kill.
+fn retire(child: &mut Child, port: Option<u16>) {
+    let asked = port.is_some_and(|port| {
+        http::post(port, "/shutdown", b"{}", Duration::from_secs(1))
+            .is_ok_and(|resp| (200..300).contains(&resp.status))
+    });
+    if asked {
+        let deadline = std::time::Instant::now() + SHUTDOWN_GRACE;
+        while child.try_wait().ok().flatten().is_none() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Ask the running backend to move to a newly scanned port through
+/// `POST /admin/rebind`. `Ok(None)` means it cannot rebind live (no such
+/// endpoint, refused, or never became healthy on the new port).
+fn try_rebind<R: Runtime>(app: &AppHandle<R>, old_port: u16) -> Result<Option<u16>, String> {
+    let reservation = free_port()?;
+    let port = reserved_port(&reservation)?;
+    let body = serde_json::json!({ "port": port }).to_string();
+    // Released just before the backend is told to bind it.
+    drop(reservation);
+    let accepted = http::post(old_port, "/admin/rebind", body.as_bytes(), REBIND_TIMEOUT)
+        .is_ok_and(|resp| (200..300).contains(&resp.status));
+    if !accepted || !health::wait_for_health(app, port, REBIND_TIMEOUT) {
+        return Ok(None);
+    }
+    set_port(app, Some(port));
+    renavigate_windows(app, Some(old_port), port);
+    Ok(Some(port))
+}
+
+/// Move the backend to a fresh port, live if it supports `/admin/rebind`,
+/// otherwise through a full restart. Returns the new port.
+pub fn rebind<R: Runtime>(app: &AppHandle<R>) -> Result<u16, String> {
+    let live = match current_port(app) {
+        Some(old_port) if !mock_backend::enabled() => exclusive(app, || try_rebind(app, old_port))?,
+        _ => None,
+    };
+    match live {
+        Some(port) => Ok(port),
+        None => restart_backend(app),
+    }
+}
+
+/// Port of the live backend, `None` while it is starting or restarting.
+#[tauri::command]
+pub fn backend_port(state: State<'_, BackendState>) -> Option<u16> {
+    state.port.lock().ok().and_then(|port| *port)
+}
+
+/// Re-run the port scan and move the backend there (e.g. after a VPN change).
+#[tauri::command]
+pub async fn rebind_backend(app: AppHandle) -> Result<u16, String> {
+    rebind(&app)
+}
+
+/// Swap the running backend for a freshly spawned one without closing windows.
+#[tauri::command]
+pub async fn hot_swap_backend(app: AppHandle) -> Result<(), String> {
+    hot_swap(&app).map(|_| ())
+}
+
+/// Restart the backend; on failure, replace the main window with the error page.
+pub fn restart_or_show_error<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(diag) = restart_backend(app) {
+        if let Some(window) = app.get_webview_window("main") {
+            let text = i18n::strings();
+            error_page::show(&window, text.restart_error_title, text.restart_failed, &diag);
+        }
+    }
+}
+
+/// Restart triggered by the app itself (health monitor): consumes the session
+/// budget, and once it is exhausted stops and waits for the user instead.
+pub fn auto_restart<R: Runtime>(app: &AppHandle<R>) {
+    if take_restart_token(app) {
+        restart_or_show_error(app);
+        return;
+    }
+    shutdown_sidecar(app);
+    if let Some(window) = app.get_webview_window("main") {
+        let text = i18n::strings();
+        error_page::show(
+            &window,
+            text.unstable_title,
+            text.unstable,
+            &format!(
+                "{} : {} {}\n{} : {OS} {ARCH}",
+                text.diag_restart_budget,
+                settings::current(app).restart_budget,
+                text.diag_per_session,
+                text.diag_system
+            ),
+        );
+    }
+}
+
+/// Automatic restarts left in this session, and the configured total.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartBudget {
+    pub remaining: u32,
+    pub total: u32,
+}
+
+#[tauri::command]
+pub fn restart_budget(app: AppHandle) -> RestartBudget {
+    let remaining = app.state::<BackendState>().restarts_left.load(Ordering::SeqCst);
+    RestartBudget { remaining, total: settings::current(&app).restart_budget }
+}
+
+/// Explicit user action: refill the budget and restart the backend.
+#[tauri::command]
+pub async fn reset_restart_budget(app: AppHandle) -> Result<u16, String> {
+    let total = settings::current(&app).restart_budget;
+    app.state::<BackendState>().restarts_left.store(total, Ordering::SeqCst);
+    restart_backend(&app)
+}
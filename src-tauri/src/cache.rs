@@ -0,0 +1,189 @@
+// ---------------------------------------------------------------------------
+// Webview disk cache: size report and soft quota
+// ---------------------------------------------------------------------------
+//
+// Over months of use the engine's HTTP cache can grow to gigabytes. A
+// low-frequency monitor measures it and, past `webview_cache_quota_mb`, asks
+// the engine to clear its disk cache only (WebKitGTK clear_cache, WebView2
+// ClearBrowsingData(DISK_CACHE), WKWebsiteDataStore disk cache): local
+// storage and cookies, which the frontend relies on, are kept.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{heartbeat, logs, settings};
+
+/// Interval between two cache size checks.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Time of the last quota-triggered (or manual) clear, RFC 3339.
+#[derive(Default)]
+pub struct CacheState(Mutex<Option<String>>);
+
+/// Folders holding the engine's disk cache. Tauri roots the webview data
+/// in the local data dir on Windows and Linux.
+fn cache_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dirs = app.path().app_local_data_dir().ok().map(|dir| {
+        let profile = dir.join("EBWebView").join("Default");
+        vec![profile.join("Cache"), profile.join("Code Cache"), profile.join("GPUCache")]
+    });
+    #[cfg(target_os = "linux")]
+    let dirs = app.path().app_local_data_dir().ok().map(|dir| vec![dir.join("WebKitCache")]);
+    #[cfg(target_os = "macos")]
+    let dirs = app.path().app_cache_dir().ok().map(|dir| vec![dir.join("WebKit")]);
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    let dirs: Option<Vec<PathBuf>> = None;
+    dirs.unwrap_or_default()
+}
+
+/// Total size of the files under *path*, symlinks not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn cache_size<R: Runtime>(app: &AppHandle<R>) -> u64 {
+    cache_dirs(app).iter().map(|dir| dir_size(dir)).sum()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_clear(webview: tauri::webview::PlatformWebview) {
+    use webkit2gtk::{WebContextExt, WebViewExt};
+    if let Some(context) = webview.inner().context() {
+        context.clear_cache();
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_clear(webview: tauri::webview::PlatformWebview) {
+    use webview2_com::ClearBrowsingDataCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2Profile2, ICoreWebView2_13, COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE,
+    };
+    use windows::core::Interface;
+    // SAFETY: COM calls on the live controller, made on the webview's thread.
+    unsafe {
+        let Ok(core) = webview.controller().CoreWebView2() else {
+            return;
+        };
+        let Ok(profile) = core
+            .cast::<ICoreWebView2_13>()
+            .and_then(|core| core.Profile())
+            .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+        else {
+            return;
+        };
+        let handler = ClearBrowsingDataCompletedHandler::create(Box::new(|_| Ok(())));
+        let _ = profile.ClearBrowsingData(COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE, &handler);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_clear(webview: tauri::webview::PlatformWebview) {
+    use objc2::{msg_send, runtime::AnyObject};
+    use objc2_foundation::{NSDate, NSSet, NSString};
+    // SAFETY: inner() is the live WKWebView; with_webview runs on the main thread.
+    unsafe {
+        let wk_webview = &*(webview.inner() as *const AnyObject);
+        let config: *mut AnyObject = msg_send![wk_webview, configuration];
+        let store: *mut AnyObject = msg_send![config, websiteDataStore];
+        let kind = NSString::from_str("WKWebsiteDataTypeDiskCache");
+        let types = NSSet::from_slice(&[&*kind]);
+        let since = NSDate::dateWithTimeIntervalSince1970(0.0);
+        let handler = block2::RcBlock::new(|| {});
+        let _: () = msg_send![
+            store,
+            removeDataOfTypes: &*types,
+            modifiedSince: &*since,
+            completionHandler: &*handler
+        ];
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn platform_clear(_webview: tauri::webview::PlatformWebview) {}
+
+/// Clear the disk cache of the main webview.
+fn clear<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Fenêtre principale introuvable")?;
+    window.with_webview(platform_clear).map_err(|e| e.to_string())?;
+    if let Some(state) = app.try_state::<CacheState>() {
+        if let Ok(mut last) = state.0.lock() {
+            *last = Some(chrono::Local::now().to_rfc3339());
+        }
+    }
+    Ok(())
+}
+
+/// Clear the cache if it exceeds the configured quota.
+fn enforce<R: Runtime>(app: &AppHandle<R>) {
+    let Some(quota_mb) = settings::current(app).webview_cache_quota_mb else {
+        return;
+    };
+    let size = cache_size(app);
+    if size > quota_mb.saturating_mul(1024 * 1024) {
+        let size_mb = size / (1024 * 1024);
+        logs::note(app, &format!("cache webview {size_mb} Mo > quota {quota_mb} Mo, vidage"));
+        let _ = clear(app);
+    }
+}
+
+/// Low-frequency thread enforcing the cache quota.
+pub fn start_monitor<R: Runtime>(app: AppHandle<R>) {
+    let heartbeat = heartbeat::register(&app, "cache-monitor", CHECK_INTERVAL);
+    let _ = std::thread::Builder::new().name("cache-monitor".into()).spawn(move || loop {
+        heartbeat.beat();
+        enforce(&app);
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatus {
+    pub size_bytes: u64,
+    pub quota_mb: Option<u64>,
+    pub dirs: Vec<String>,
+    pub last_cleared: Option<String>,
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> CacheStatus {
+    CacheStatus {
+        size_bytes: cache_size(app),
+        quota_mb: settings::current(app).webview_cache_quota_mb,
+        dirs: cache_dirs(app).iter().map(|d| d.display().to_string()).collect(),
+        last_cleared: app
+            .try_state::<CacheState>()
+            .and_then(|state| state.0.lock().ok().and_then(|last| last.clone())),
+    }
+}
+
+/// Current size of the webview disk cache and its quota.
+#[tauri::command]
+pub async fn webview_cache_status(app: AppHandle) -> CacheStatus {
+    status(&app)
+}
+
+/// Set the soft quota in megabytes (`None` removes it); applied right away.
+#[tauri::command]
+pub async fn set_webview_cache_quota(app: AppHandle, mb: Option<u64>) -> Result<CacheStatus, String> {
+    if mb == Some(0) {
+        return Err("Le quota doit être d'au moins 1 Mo".to_string());
+    }
+    settings::update(&app, |s| s.webview_cache_quota_mb = mb)?;
+    enforce(&app);
+    Ok(status(&app))
+}
@@ -0,0 +1,93 @@
+// ---------------------------------------------------------------------------
+// Fault injection for QA of the error and reconnect flows (debug builds only)
+// ---------------------------------------------------------------------------
+//
+// The backend is asked to misbehave through `POST /admin/chaos` when it
+// supports it; otherwise the shell acts on the child process directly:
+//   - crash : kill it;
+//   - hang  : SIGSTOP it, so TCP still connects but HTTP never answers;
+//   - slow  : stop and resume it in cycles for a while.
+// Stopping a process is only available on Unix.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::backend::{self, SidecarState};
+use crate::http;
+
+/// How long the `slow` fallback keeps throttling the backend.
+#[cfg(unix)]
+const SLOW_FOR: Duration = Duration::from_secs(60);
+
+const KINDS: &[&str] = &["crash", "hang", "slow"];
+
+fn backend_pid<R: Runtime>(app: &AppHandle<R>) -> Option<u32> {
+    app.state::<SidecarState>().0.lock().ok()?.as_ref().map(|child| child.id())
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, sig: &str) -> Result<(), String> {
+    let status = std::process::Command::new("kill")
+        .args([sig, &pid.to_string()])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("kill {sig} {pid} a échoué"))
+    }
+}
+
+#[cfg(unix)]
+fn stop(pid: u32, slow: bool) -> Result<(), String> {
+    if !slow {
+        return signal(pid, "-STOP");
+    }
+    std::thread::spawn(move || {
+        let until = std::time::Instant::now() + SLOW_FOR;
+        while std::time::Instant::now() < until {
+            if signal(pid, "-STOP").is_err() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(1500));
+            let _ = signal(pid, "-CONT");
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn stop(_pid: u32, _slow: bool) -> Result<(), String> {
+    Err("Suspendre le serveur n'est possible que sous macOS et Linux".to_string())
+}
+
+/// Make the running backend `crash`, `hang` or go `slow`.
+#[tauri::command]
+pub fn simulate_backend_failure(app: AppHandle, kind: String) -> Result<(), String> {
+    if !KINDS.contains(&kind.as_str()) {
+        return Err(format!("Panne inconnue « {kind} » (attendu : {})", KINDS.join(", ")));
+    }
+    let port = backend::current_port(&app).ok_or("Serveur non démarré")?;
+    let body = serde_json::json!({ "kind": kind }).to_string();
+    let handled = http::post(port, "/admin/chaos", body.as_bytes(), Duration::from_secs(3))
+        .is_ok_and(|resp| (200..300).contains(&resp.status));
+    if handled {
+        return Ok(());
+    }
+
+    let pid = backend_pid(&app).ok_or("Aucun processus serveur à perturber")?;
+    match kind.as_str() {
+        "crash" => {
+            let state = app.state::<SidecarState>();
+            let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+            if let Some(child) = guard.as_mut() {
+                child.kill().map_err(|e| e.to_string())?;
+                let _ = child.wait();
+            }
+            Ok(())
+        }
+        other => stop(pid, other == "slow"),
+    }
+}
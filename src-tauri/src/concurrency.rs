@@ -0,0 +1,158 @@
+// ---------------------------------------------------------------------------
+// Backend worker concurrency, optionally lowered on battery power
+// ---------------------------------------------------------------------------
+//
+// The backend exposes `POST /admin/concurrency` to resize its worker pool
+// live. The explicit value is saved as `backend_concurrency`; when
+// `battery_concurrency` is set, a power monitor switches between the two as
+// the machine goes on and off mains power.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+use crate::{backend, heartbeat, http, i18n, settings};
+
+/// Interval between two power-source checks.
+const POWER_INTERVAL: Duration = Duration::from_secs(30);
+
+const ADMIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// No battery found, or the platform could not tell.
+    Unknown,
+}
+
+#[cfg(target_os = "linux")]
+fn power_source() -> PowerSource {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Unknown;
+    };
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name)).map(|s| s.trim().to_string()).unwrap_or_default()
+    };
+    let mut source = PowerSource::Unknown;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        match read(&dir, "type").as_str() {
+            "Mains" if read(&dir, "online") == "1" => return PowerSource::Ac,
+            "Battery" if read(&dir, "status") == "Discharging" => source = PowerSource::Battery,
+            _ => {}
+        }
+    }
+    source
+}
+
+#[cfg(target_os = "macos")]
+fn power_source() -> PowerSource {
+    let Ok(output) = std::process::Command::new("pmset").args(["-g", "batt"]).output() else {
+        return PowerSource::Unknown;
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if text.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn power_source() -> PowerSource {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    // SAFETY: plain out-parameter, zero-initialised POD struct.
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerSource::Unknown;
+    }
+    match status.ACLineStatus {
+        0 => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown,
+    }
+}
+
+/// Ask the backend on *port* to run *workers* worker threads.
+fn apply(port: u16, workers: u32) -> Result<(), String> {
+    let text = i18n::strings();
+    let body = serde_json::json!({ "workers": workers }).to_string();
+    let resp = http::post(port, "/admin/concurrency", body.as_bytes(), ADMIN_TIMEOUT)
+        .map_err(|e| format!("{} {e}", text.server_unreachable))?;
+    match resp.status {
+        200..=299 => Ok(()),
+        404 | 405 | 501 => Err(text.concurrency_unsupported.to_string()),
+        status => Err(format!("{} (HTTP {status}) : {}", text.concurrency_refused, resp.text())),
+    }
+}
+
+/// Worker count wanted for *source*, `None` to leave the backend as it is.
+fn wanted(current: &settings::Settings, source: PowerSource) -> Option<u32> {
+    match source {
+        PowerSource::Battery => current.battery_concurrency.or(current.backend_concurrency),
+        PowerSource::Ac | PowerSource::Unknown => current.backend_concurrency,
+    }
+}
+
+/// Thread re-applying the wanted concurrency when the power source changes
+/// or the backend moves (restart, rebind), which resets its worker pool.
+pub fn start_monitor<R: Runtime>(app: AppHandle<R>) {
+    let heartbeat = heartbeat::register(&app, "power-monitor", POWER_INTERVAL);
+    let _ = std::thread::Builder::new().name("power-monitor".into()).spawn(move || {
+        let mut applied: Option<(u16, u32)> = None;
+        loop {
+            heartbeat.beat();
+            let source = power_source();
+            if let (Some(port), Some(workers)) =
+                (backend::current_port(&app), wanted(&settings::current(&app), source))
+            {
+                if applied != Some((port, workers)) {
+                    // A backend without the endpoint is not retried every tick.
+                    let _ = apply(port, workers);
+                    applied = Some((port, workers));
+                }
+            }
+            std::thread::sleep(POWER_INTERVAL);
+        }
+    });
+}
+
+/// Resize the backend worker pool to *n* and keep it as the mains-power value.
+#[tauri::command]
+pub async fn set_backend_concurrency(app: AppHandle, n: u32) -> Result<u32, String> {
+    let text = i18n::strings();
+    if n == 0 {
+        return Err(text.at_least_one_worker.to_string());
+    }
+    let port = backend::current_port(&app).ok_or(text.server_not_started)?;
+    let on_battery = power_source() == PowerSource::Battery;
+    let battery_override = settings::current(&app).battery_concurrency.is_some();
+    if !(on_battery && battery_override) {
+        apply(port, n)?;
+    }
+    settings::update(&app, |s| s.backend_concurrency = Some(n))?;
+    Ok(n)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcurrencyState {
+    pub power: PowerSource,
+    pub backend_concurrency: Option<u32>,
+    pub battery_concurrency: Option<u32>,
+}
+
+/// Power source and configured worker counts.
+#[tauri::command]
+pub fn backend_concurrency(app: AppHandle) -> ConcurrencyState {
+    let current = settings::current(&app);
+    ConcurrencyState {
+        power: power_source(),
+        backend_concurrency: current.backend_concurrency,
+        battery_concurrency: current.battery_concurrency,
+    }
+}
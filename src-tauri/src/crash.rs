@@ -0,0 +1,60 @@
+// ---------------------------------------------------------------------------
+// Crash reports: panics of the native shell are written to the log dir
+// ---------------------------------------------------------------------------
+
+use std::backtrace::Backtrace;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::disk;
+
+/// Folder holding one `crash-*.txt` file per panic.
+pub fn reports_dir<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_log_dir().ok().map(|dir| dir.join("crashes"))
+}
+
+fn write_report(dir: &Path, message: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let now = chrono::Local::now();
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    let mut file = std::fs::File::create(&path)?;
+    let written = write_body(&mut file, message, &now);
+    if written.is_err() {
+        // A truncated report (typically on a full disk) is worse than none.
+        drop(file);
+        let _ = std::fs::remove_file(&path);
+    }
+    written.map(|()| path)
+}
+
+fn write_body(
+    file: &mut std::fs::File,
+    message: &str,
+    now: &chrono::DateTime<chrono::Local>,
+) -> std::io::Result<()> {
+    writeln!(file, "Tablerreur {} — {}", env!("CARGO_PKG_VERSION"), now.to_rfc3339())?;
+    writeln!(file, "Système : {} {}", std::env::consts::OS, std::env::consts::ARCH)?;
+    writeln!(file, "Thread : {}", std::thread::current().name().unwrap_or("<sans nom>"))?;
+    writeln!(file, "\n{message}\n\n{}", Backtrace::force_capture())?;
+    file.flush()
+}
+
+/// Chain a panic hook that writes a crash report before the default output.
+/// Any I/O failure just means no report is kept; a full disk is reported.
+pub fn install_hook<R: Runtime>(app: &AppHandle<R>) {
+    let Some(dir) = reports_dir(app) else {
+        return;
+    };
+    let app = app.clone();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Err(e) = write_report(&dir, &info.to_string()) {
+            if disk::is_full(&e) {
+                disk::report(&app, "crash");
+            }
+        }
+        previous(info);
+    }));
+}
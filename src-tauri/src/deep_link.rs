@@ -0,0 +1,210 @@
+// ---------------------------------------------------------------------------
+// Deep links: tablerreur://report/{id} opens that report in the main window
+// ---------------------------------------------------------------------------
+//
+// Windows and Linux hand the link to a new process as its first argument; a
+// second launch passes it to the running instance over the single-instance
+// handshake. macOS delivers it to the running app as `RunEvent::Opened`.
+// Either way the link becomes a path on the backend: opened at once when the
+// backend is up, or kept until `mark_ready` points the window at it.
+//
+// The scheme is declared in Info.plist for macOS and registered for the
+// current user at launch elsewhere. Debug builds do not register, so a dev
+// binary never takes over links meant for the installed app.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, Url};
+
+use crate::backend;
+
+pub const SCHEME: &str = "tablerreur";
+
+/// Longest report id accepted, in digits.
+const MAX_ID_LEN: usize = 18;
+
+/// Backend path of the last link received before the backend was ready.
+#[derive(Default)]
+pub struct PendingLink(pub Mutex<Option<String>>);
+
+/// Backend path for *link*, or `None` if it is not a well-formed
+/// `tablerreur://report/{digits}`, with nothing else: no query, fragment,
+/// credentials or port.
+pub fn backend_path(link: &str) -> Option<String> {
+    let url = Url::parse(link.trim()).ok()?;
+    let bare = url.query().is_none()
+        && url.fragment().is_none()
+        && url.username().is_empty()
+        && url.password().is_none()
+        && url.port().is_none();
+    if !bare || url.scheme() != SCHEME || url.host_str() != Some("report") {
+        return None;
+    }
+    let id = url.path().strip_prefix('/')?;
+    let id = id.strip_suffix('/').unwrap_or(id);
+    let valid = !id.is_empty() && id.len() <= MAX_ID_LEN && id.bytes().all(|b| b.is_ascii_digit());
+    valid.then(|| format!("/report/{id}"))
+}
+
+/// The deep link this process was launched with, if any.
+pub fn from_args() -> Option<String> {
+    std::env::args().skip(1).find(|arg| arg.starts_with(&format!("{SCHEME}:")))
+}
+
+/// Open *link* in the main window, or queue it until the backend is ready.
+/// Malformed links are ignored.
+pub fn open<R: Runtime>(app: &AppHandle<R>, link: &str) {
+    let Some(path) = backend_path(link) else {
+        return;
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    match backend::current_port(app) {
+        Some(port) => {
+            let target = format!("http://127.0.0.1:{port}{path}").parse::<Url>();
+            if let (Ok(url), Some(window)) = (target, app.get_webview_window("main")) {
+                let _ = window.navigate(url);
+            }
+        }
+        None => {
+            if let Some(pending) = app.try_state::<PendingLink>() {
+                if let Ok(mut guard) = pending.0.lock() {
+                    *guard = Some(path);
+                }
+            }
+        }
+    }
+}
+
+/// Take the queued backend path, for the navigation that follows startup.
+pub fn take_pending<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.try_state::<PendingLink>()
+        .and_then(|pending| pending.0.lock().ok().and_then(|mut guard| guard.take()))
+}
+
+/// Register the scheme for the current user, pointing at this executable.
+/// The `reg` calls run on a thread of their own.
+#[cfg(target_os = "windows")]
+pub fn register() {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    if cfg!(debug_assertions) {
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let key = format!(r"HKCU\Software\Classes\{SCHEME}");
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        let entries: [&[&str]; 3] = [
+            &[&key, "/ve", "/d", "URL:Tablerreur", "/f"],
+            &[&key, "/v", "URL Protocol", "/d", "", "/f"],
+            &[&format!(r"{key}\shell\open\command"), "/ve", "/d", &command, "/f"],
+        ];
+        for args in entries {
+            let _ = std::process::Command::new("reg")
+                .arg("add")
+                .args(args)
+                .creation_flags(CREATE_NO_WINDOW)
+                .output();
+        }
+    });
+}
+
+/// Register the scheme for the current user, pointing at this executable.
+/// The data directory is resolved on the calling thread, since setup may
+/// still set environment variables; the desktop entry and `xdg-mime` are
+/// handled on a thread of their own, and skipped when the entry is already
+/// in place.
+#[cfg(target_os = "linux")]
+pub fn register() {
+    if cfg!(debug_assertions) {
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let data = std::env::var_os("XDG_DATA_HOME").map(std::path::PathBuf::from).or_else(|| {
+        std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/share"))
+    });
+    let Some(dir) = data.map(|data| data.join("applications")) else {
+        return;
+    };
+    std::thread::spawn(move || write_desktop_entry(&exe, &dir));
+}
+
+#[cfg(target_os = "linux")]
+fn write_desktop_entry(exe: &std::path::Path, dir: &std::path::Path) {
+    let name = format!("{SCHEME}-url.desktop");
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Tablerreur\nExec=\"{}\" %u\n\
+         NoDisplay=true\nMimeType=x-scheme-handler/{SCHEME};\n",
+        exe.display()
+    );
+    let path = dir.join(&name);
+    if std::fs::read_to_string(&path).is_ok_and(|current| current == entry) {
+        return;
+    }
+    if std::fs::create_dir_all(dir).is_err() || std::fs::write(&path, entry).is_err() {
+        return;
+    }
+    let _ = std::process::Command::new("xdg-mime")
+        .args(["default", &name, &format!("x-scheme-handler/{SCHEME}")])
+        .output();
+}
+
+/// Declared in the bundle's Info.plist: nothing to do at runtime.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn register() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_report_links() {
+        assert_eq!(backend_path("tablerreur://report/42").as_deref(), Some("/report/42"));
+        assert_eq!(backend_path("tablerreur://report/42/").as_deref(), Some("/report/42"));
+        assert_eq!(backend_path(" tablerreur://report/7\n").as_deref(), Some("/report/7"));
+        assert_eq!(
+            backend_path("tablerreur://report/123456789012345678").as_deref(),
+            Some("/report/123456789012345678")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_ids() {
+        assert_eq!(backend_path("tablerreur://report/"), None);
+        assert_eq!(backend_path("tablerreur://report/abc"), None);
+        assert_eq!(backend_path("tablerreur://report/12a"), None);
+        assert_eq!(backend_path("tablerreur://report/-1"), None);
+        assert_eq!(backend_path("tablerreur://report/1234567890123456789"), None);
+        assert_eq!(backend_path("tablerreur://report/%34%32"), None);
+        assert_eq!(backend_path("tablerreur://report/..%2F42"), None);
+    }
+
+    #[test]
+    fn rejects_anything_beyond_the_id() {
+        assert_eq!(backend_path("tablerreur://report/42/edit"), None);
+        assert_eq!(backend_path("tablerreur://report/42//"), None);
+        assert_eq!(backend_path("tablerreur://report/42?admin=1"), None);
+        assert_eq!(backend_path("tablerreur://report/42?"), None);
+        assert_eq!(backend_path("tablerreur://report/42#top"), None);
+        assert_eq!(backend_path("tablerreur://user@report/42"), None);
+        assert_eq!(backend_path("tablerreur://report:8400/42"), None);
+    }
+
+    #[test]
+    fn rejects_other_hosts_and_schemes() {
+        assert_eq!(backend_path("tablerreur://admin/42"), None);
+        assert_eq!(backend_path("tablerreur://reports/42"), None);
+        assert_eq!(backend_path("tablerreur:report/42"), None);
+        assert_eq!(backend_path("http://report/42"), None);
+        assert_eq!(backend_path("not a link"), None);
+    }
+}
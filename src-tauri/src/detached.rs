@@ -0,0 +1,141 @@
+// ---------------------------------------------------------------------------
+// Detached backends: surviving the UI, adopted by the next launch
+// ---------------------------------------------------------------------------
+//
+// With `on_exit = "detach"` closing the app leaves the backend running and
+// records its PID and port in `detached-backend.json`. The next launch adopts
+// it if it is still healthy and from the same version; otherwise it is
+// terminated and a fresh one spawned. There is only ever one record, so
+// detached backends cannot pile up.
+//
+// Output capture ends with the UI that piped it: a detached backend must
+// tolerate a closed stdout/stderr.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::backend::{self, SidecarState};
+use crate::health::HEALTH_PATH;
+use crate::{http, ports, settings};
+
+const RECORD_FILE: &str = "detached-backend.json";
+
+/// How long an adoption candidate gets to answer its health probe.
+const ADOPT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What closing the app does to the backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitMode {
+    #[default]
+    Kill,
+    Detach,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    pid: u32,
+    port: u16,
+    version: String,
+}
+
+/// PID of a backend adopted from a previous launch: there is no `Child`
+/// for it, so it is stopped by PID.
+#[derive(Default)]
+pub struct AdoptedBackend(pub Mutex<Option<u32>>);
+
+fn record_path<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(RECORD_FILE))
+}
+
+fn read_record<R: Runtime>(app: &impl Manager<R>) -> Option<Record> {
+    let text = std::fs::read_to_string(record_path(app)?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn remove_record<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(path) = record_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// PID of the detached backend this instance adopted, if any.
+pub fn adopted_pid<R: Runtime>(app: &impl Manager<R>) -> Option<u32> {
+    app.try_state::<AdoptedBackend>()?.0.lock().ok().and_then(|pid| *pid)
+}
+
+/// Reuse the backend a previous launch left running, if it is still ours and
+/// healthy. Returns its port; `None` means a fresh backend must be spawned.
+pub fn adopt<R: Runtime>(app: &AppHandle<R>) -> Option<u16> {
+    let record = read_record(app)?;
+    // PIDs are recycled: only a process still listening on the recorded port
+    // is taken to be the detached backend.
+    let ours = ports::holder_pid(record.port) == Some(record.pid);
+    let healthy = ours
+        && http::get(record.port, HEALTH_PATH, ADOPT_PROBE_TIMEOUT).is_ok_and(|r| r.status == 200);
+    if healthy && record.version == app.package_info().version.to_string() {
+        if let Some(state) = app.try_state::<AdoptedBackend>() {
+            if let Ok(mut pid) = state.0.lock() {
+                *pid = Some(record.pid);
+            }
+        }
+        return Some(record.port);
+    }
+    if ours {
+        let _ = ports::terminate(record.pid);
+    }
+    remove_record(app);
+    None
+}
+
+/// Stop the adopted backend, if any. Called by `backend::kill_sidecar`.
+pub fn kill_adopted<R: Runtime>(app: &impl Manager<R>) {
+    let Some(state) = app.try_state::<AdoptedBackend>() else {
+        return;
+    };
+    let pid = state.0.lock().ok().and_then(|mut pid| pid.take());
+    if let Some(pid) = pid {
+        let _ = ports::terminate(pid);
+        remove_record(app);
+    }
+}
+
+/// Leave the backend running and record it for the next launch. The `Child`
+/// is only let go once the record is written: on error it stays in
+/// `SidecarState` for the shutdown that follows.
+fn detach<R: Runtime>(app: &impl Manager<R>) -> Result<(), String> {
+    let port = backend::current_port(app).ok_or("Serveur non démarré")?;
+    let child_pid = app
+        .try_state::<SidecarState>()
+        .and_then(|state| state.0.lock().ok().and_then(|child| child.as_ref().map(|c| c.id())));
+    let pid = child_pid.or_else(|| adopted_pid(app)).ok_or("Aucun serveur")?;
+    let record = Record { pid, port, version: app.package_info().version.to_string() };
+    let path = record_path(app).ok_or("Impossible de résoudre app_data_dir")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))?;
+    // Dropping the `Child` neither kills nor waits for the process; the
+    // detached record now stands in for the PID file.
+    let child = app
+        .try_state::<SidecarState>()
+        .and_then(|state| state.0.lock().ok().and_then(|mut child| child.take()));
+    backend::remove_pid_file(app);
+    drop(child);
+    Ok(())
+}
+
+/// Exit path (Quitter, window close, scheduled app restart): shut down or
+/// detach the backend according to `on_exit`. Detaching falls back to a
+/// shutdown when the backend cannot be recorded.
+pub fn on_exit<R: Runtime>(app: &impl Manager<R>) {
+    let detach_ok = settings::current(app).on_exit == ExitMode::Detach && detach(app).is_ok();
+    if !detach_ok {
+        backend::shutdown_sidecar(app);
+    }
+}
@@ -0,0 +1,275 @@
+// ---------------------------------------------------------------------------
+// Diagnostic commands
+// ---------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::{backend, health, i18n, timings};
+
+/// Reachability of the backend on each loopback family.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoopbackInfo {
+    pub port: Option<u16>,
+    /// Something accepts TCP on 127.0.0.1:{port}.
+    pub ipv4: bool,
+    /// Something accepts TCP on [::1]:{port}.
+    pub ipv6: bool,
+    /// Host the main window is navigated to (`127.0.0.1` unless changed).
+    pub navigation_host: Option<String>,
+}
+
+fn loopback_info<R: Runtime>(app: &AppHandle<R>) -> LoopbackInfo {
+    let port = backend::current_port(app);
+    let (ipv4, ipv6) = port
+        .map(|port| health::probe_dual_stack(port, Duration::from_millis(500)))
+        .unwrap_or_default();
+    let navigation_host = app
+        .get_webview_window("main")
+        .and_then(|window| window.url().ok())
+        .and_then(|url| url.host_str().map(str::to_string));
+    LoopbackInfo { port, ipv4, ipv6, navigation_host }
+}
+
+/// Report whether the backend answers on `127.0.0.1` and `[::1]`, and which
+/// one the main window uses.
+#[tauri::command]
+pub async fn loopback_status(app: AppHandle) -> LoopbackInfo {
+    loopback_info(&app)
+}
+
+// ---------------------------------------------------------------------------
+// Network environment: proxies, name resolution and local filtering, the
+// usual suspects when the app works on one machine and not on another
+// ---------------------------------------------------------------------------
+
+const PROXY_VARS: &[&str] = &[
+    "HTTP_PROXY", "http_proxy", "HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy",
+    "NO_PROXY", "no_proxy",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyReport {
+    /// Proxy variables set in the app's environment.
+    pub env: BTreeMap<String, String>,
+    /// System proxy in force, as reported by the OS, `None` when direct.
+    pub system: Option<String>,
+    /// `NO_PROXY` (or the system exception list) covers 127.0.0.1.
+    pub bypasses_loopback: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkReport {
+    /// Backend port in use, if started.
+    pub port: Option<u16>,
+    pub loopback: LoopbackInfo,
+    /// `localhost` resolves to 127.0.0.1 / ::1.
+    pub localhost_v4: bool,
+    pub localhost_v6: bool,
+    pub proxy: ProxyReport,
+    /// A throwaway listener in the backend port range accepted a loopback
+    /// connection; false suggests a firewall or security suite filters it.
+    pub range_loopback_ok: bool,
+    pub firewall_suspected: bool,
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// System proxy and its exception list, best effort.
+#[cfg(target_os = "windows")]
+fn system_proxy() -> (Option<String>, String) {
+    let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings";
+    let out = command_output("reg", &["query", key]).unwrap_or_default();
+    let value = |name: &str| {
+        out.lines()
+            .map(str::split_whitespace)
+            .find_map(|mut parts| (parts.next() == Some(name)).then(|| parts.nth(1).map(str::to_string)))
+            .flatten()
+    };
+    let enabled = value("ProxyEnable").is_some_and(|v| v == "0x1");
+    let server = enabled.then(|| value("ProxyServer")).flatten();
+    let auto = value("AutoConfigURL").map(|url| format!("PAC {url}"));
+    (server.or(auto), value("ProxyOverride").unwrap_or_default())
+}
+
+#[cfg(target_os = "macos")]
+fn system_proxy() -> (Option<String>, String) {
+    let out = command_output("scutil", &["--proxy"]).unwrap_or_default();
+    let value = |name: &str| {
+        out.lines()
+            .filter_map(|l| l.split_once(" : "))
+            .find(|(k, _)| k.trim() == name)
+            .map(|(_, v)| v.trim().to_string())
+    };
+    let server = ["HTTP", "HTTPS", "SOCKS"].iter().find_map(|kind| {
+        (value(&format!("{kind}Enable")).as_deref() == Some("1")).then(|| {
+            let host = value(&format!("{kind}Proxy")).unwrap_or_default();
+            let port = value(&format!("{kind}Port")).unwrap_or_default();
+            format!("{kind} {host}:{port}")
+        })
+    });
+    let pac = (value("ProxyAutoConfigEnable").as_deref() == Some("1"))
+        .then(|| format!("PAC {}", value("ProxyAutoConfigURLString").unwrap_or_default()));
+    // ExceptionsList is multi-line; its entries are just listed in the output.
+    let exceptions = if out.contains("127.0.0.1") || out.contains("localhost") {
+        "127.0.0.1".to_string()
+    } else {
+        String::new()
+    };
+    (server.or(pac), exceptions)
+}
+
+#[cfg(target_os = "linux")]
+fn system_proxy() -> (Option<String>, String) {
+    let get = |key: &str| {
+        command_output("gsettings", &["get", "org.gnome.system.proxy", key])
+            .map(|v| v.trim().trim_matches('\'').to_string())
+    };
+    let mode = get("mode").unwrap_or_default();
+    let server = match mode.as_str() {
+        "manual" => {
+            let http = |key: &str| {
+                command_output("gsettings", &["get", "org.gnome.system.proxy.http", key])
+                    .map(|v| v.trim().trim_matches('\'').to_string())
+                    .unwrap_or_default()
+            };
+            Some(format!("manuel {}:{}", http("host"), http("port")))
+        }
+        "auto" => Some(format!("PAC {}", get("autoconfig-url").unwrap_or_default())),
+        _ => None,
+    };
+    (server, get("ignore-hosts").unwrap_or_default())
+}
+
+fn covers_loopback(list: &str) -> bool {
+    list.split([',', ';', ' ', '[', ']', '\''])
+        .map(str::trim)
+        .any(|entry| matches!(entry, "*" | "127.0.0.1" | "localhost" | "127.0.0.0/8" | "<local>"))
+}
+
+fn proxy_report() -> ProxyReport {
+    let env: BTreeMap<String, String> = PROXY_VARS
+        .iter()
+        .filter_map(|name| std::env::var(name).ok().map(|v| (name.to_string(), v)))
+        .collect();
+    let (system, exceptions) = system_proxy();
+    let no_proxy = ["NO_PROXY", "no_proxy"].iter().filter_map(|n| env.get(*n)).any(|v| covers_loopback(v));
+    ProxyReport { env, system, bypasses_loopback: no_proxy || covers_loopback(&exceptions) }
+}
+
+/// Whether `localhost` resolves to an IPv4 and an IPv6 loopback address.
+fn localhost_resolution() -> (bool, bool) {
+    let addrs: Vec<SocketAddr> = ("localhost", 0).to_socket_addrs().map(Iterator::collect).unwrap_or_default();
+    (
+        addrs.iter().any(|a| a.is_ipv4() && a.ip().is_loopback()),
+        addrs.iter().any(|a| a.is_ipv6() && a.ip().is_loopback()),
+    )
+}
+
+/// Open a listener on a free port of the backend range and connect to it.
+fn range_loopback_ok() -> bool {
+    let limits = backend::limits();
+    let Some(listener) = backend::reserve_free_port(limits.port_min, limits.port_max) else {
+        return false;
+    };
+    let Ok(addr) = listener.local_addr() else {
+        return false;
+    };
+    TcpStream::connect_timeout(&addr, Duration::from_secs(1)).is_ok()
+}
+
+/// Consolidated network environment for support: proxies, loopback
+/// resolution and reachability, filtering of the port range, chosen port.
+pub fn network_report<R: Runtime>(app: &AppHandle<R>) -> NetworkReport {
+    let (localhost_v4, localhost_v6) = localhost_resolution();
+    let range_loopback_ok = range_loopback_ok();
+    let loopback = loopback_info(app);
+    let backend_blocked = loopback.port.is_some() && !loopback.ipv4;
+    NetworkReport {
+        port: backend::current_port(app),
+        firewall_suspected: !range_loopback_ok || backend_blocked,
+        loopback,
+        localhost_v4,
+        localhost_v6,
+        proxy: proxy_report(),
+        range_loopback_ok,
+    }
+}
+
+/// Report the effective network environment (proxy, loopback, firewall).
+#[tauri::command]
+pub async fn network_diagnostics(app: AppHandle) -> NetworkReport {
+    network_report(&app)
+}
+
+// ---------------------------------------------------------------------------
+// Frontend reachability: the webview's own network stack (proxy settings,
+// firewall rules) may block loopback even when the Rust-side probe succeeds
+// ---------------------------------------------------------------------------
+
+/// How long the page has, after navigation, to confirm it reached the backend.
+const FRONTEND_CONFIRM_WINDOW: Duration = Duration::from_secs(20);
+
+/// Managed state: whether the page loaded since the last navigation to the
+/// backend has confirmed connectivity.
+#[derive(Default)]
+pub struct FrontendReach {
+    confirmed: AtomicBool,
+    /// Bumped on every navigation so an older watcher stands down.
+    generation: AtomicU64,
+}
+
+/// Called right after navigating to the backend: unless the page calls
+/// `frontend_can_reach` within the window, show a proxy/firewall hint.
+pub fn await_frontend<R: Runtime>(app: &AppHandle<R>) {
+    let Some(reach) = app.try_state::<FrontendReach>() else {
+        return;
+    };
+    reach.confirmed.store(false, Ordering::SeqCst);
+    let generation = reach.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(FRONTEND_CONFIRM_WINDOW);
+        let reach = app.state::<FrontendReach>();
+        let superseded = reach.generation.load(Ordering::SeqCst) != generation;
+        let confirmed = reach.confirmed.load(Ordering::SeqCst);
+        if superseded || confirmed || backend::current_port(&app).is_none() {
+            return;
+        }
+        let text = i18n::strings();
+        app.dialog()
+            .message(text.loopback_blocked)
+            .title(text.loopback_blocked_title)
+            .kind(MessageDialogKind::Warning)
+            .show(|_| {});
+    });
+}
+
+/// Called by the page after a successful fetch to the backend.
+#[tauri::command]
+pub fn frontend_can_reach(app: AppHandle) -> bool {
+    app.state::<FrontendReach>().confirmed.store(true, Ordering::SeqCst);
+    if !timings::traced(&app, "frontend_ready") {
+        timings::trace(&app, "frontend_ready", serde_json::Value::Null);
+        let _ = timings::write_trace(&app);
+    }
+    true
+}
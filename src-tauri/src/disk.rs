@@ -0,0 +1,45 @@
+// ---------------------------------------------------------------------------
+// Disk-full handling for the shell's own writers
+// ---------------------------------------------------------------------------
+//
+// Log capture, crash reports and support packages all write to the user's
+// disk. When it fills up they stop writing instead of retrying, and the user
+// is warned once per session through a `disk-full` event and a dialog.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::i18n;
+
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether *err* means the volume has no space left.
+pub fn is_full(err: &io::Error) -> bool {
+    if err.kind() == io::ErrorKind::StorageFull || err.kind() == io::ErrorKind::QuotaExceeded {
+        return true;
+    }
+    // ERROR_HANDLE_DISK_FULL and ERROR_DISK_FULL
+    #[cfg(windows)]
+    if matches!(err.raw_os_error(), Some(39 | 112)) {
+        return true;
+    }
+    false
+}
+
+/// Emit `disk-full` and show a non-blocking warning, once per session.
+/// *what* names the writer that hit the condition (`logs`, `support`, …).
+pub fn report<R: Runtime>(app: &AppHandle<R>, what: &str) {
+    if REPORTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let _ = app.emit("disk-full", serde_json::json!({ "source": what }));
+    let text = i18n::strings();
+    app.dialog()
+        .message(text.disk_full)
+        .title(text.disk_full_title)
+        .kind(MessageDialogKind::Warning)
+        .show(|_| {});
+}
@@ -0,0 +1,113 @@
+// ---------------------------------------------------------------------------
+// Backend state dumps for support bundles
+// ---------------------------------------------------------------------------
+//
+// `POST /admin/dump` makes the backend dump its internal state. It either
+// answers with the dump itself, or with a small JSON `{"url": "/admin/dump/…"}`
+// naming the file to download. Either way the body is streamed to
+// `dumps/` in the data dir, never held in memory: dumps can be large.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{backend, disk, http};
+
+const DUMP_DIR: &str = "dumps";
+
+/// Dumping a large state can take a while.
+const DUMP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A JSON reply larger than this is the dump itself, not a pointer to it.
+const MAX_POINTER_BYTES: u64 = 64 * 1024;
+
+/// Folder of the saved dumps.
+pub fn dumps_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(DUMP_DIR))
+}
+
+/// Most recent dump, if any (names sort by their timestamp).
+pub fn latest<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    std::fs::read_dir(dumps_dir(app)?)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("backend-dump-"))
+        .map(|entry| entry.path())
+        .max()
+}
+
+/// Stream the response of *method* *path* into a new file at *dest*.
+fn download(port: u16, method: &str, path: &str, dest: &Path) -> io::Result<http::Head> {
+    let body = (method == "POST").then_some(&b"{}"[..]);
+    let mut out = BufWriter::new(File::create(dest)?);
+    let (head, _) = http::stream_to(port, method, path, body, DUMP_TIMEOUT, &mut out)?;
+    // Sync explicitly so a late ENOSPC is not swallowed on drop.
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    Ok(head)
+}
+
+/// Download URL named by a `{"url": …}` reply, if that is what *file* holds.
+fn pointer(head: &http::Head, file: &Path) -> Option<String> {
+    let json = head.header("Content-Type").is_some_and(|t| t.starts_with("application/json"));
+    if !json || std::fs::metadata(file).ok()?.len() > MAX_POINTER_BYTES {
+        return None;
+    }
+    let mut text = String::new();
+    File::open(file).ok()?.read_to_string(&mut text).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&text).ok()?;
+    value.get("url")?.as_str().filter(|url| url.starts_with('/')).map(str::to_string)
+}
+
+fn extension(head: &http::Head) -> &'static str {
+    match head.header("Content-Type").unwrap_or_default() {
+        t if t.starts_with("application/json") => "json",
+        t if t.starts_with("text/") => "txt",
+        t if t.starts_with("application/zip") => "zip",
+        _ => "bin",
+    }
+}
+
+fn check_status(head: &http::Head) -> Result<(), String> {
+    match head.status {
+        200..=299 => Ok(()),
+        404 | 405 | 501 => Err("Ce serveur ne permet pas d'exporter son état".to_string()),
+        status => Err(format!("Export refusé (HTTP {status})")),
+    }
+}
+
+fn io_error<R: Runtime>(app: &AppHandle<R>, e: io::Error) -> String {
+    if disk::is_full(&e) {
+        disk::report(app, "dump");
+        return "Disque plein : l'état du serveur n'a pas pu être enregistré".to_string();
+    }
+    format!("Export de l'état du serveur impossible : {e}")
+}
+
+fn capture<R: Runtime>(app: &AppHandle<R>, dir: &Path, partial: &Path) -> Result<PathBuf, String> {
+    let port = backend::current_port(app).ok_or("Serveur non démarré")?;
+    std::fs::create_dir_all(dir).map_err(|e| io_error(app, e))?;
+    let mut head = download(port, "POST", "/admin/dump", partial).map_err(|e| io_error(app, e))?;
+    check_status(&head)?;
+    if let Some(url) = pointer(&head, partial) {
+        head = download(port, "GET", &url, partial).map_err(|e| io_error(app, e))?;
+        check_status(&head)?;
+    }
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("backend-dump-{stamp}.{}", extension(&head)));
+    std::fs::rename(partial, &path).map_err(|e| io_error(app, e))?;
+    Ok(path)
+}
+
+/// Ask the backend to dump its state and save it in the data dir, returning
+/// the file path. The latest dump is added to support packages.
+#[tauri::command]
+pub async fn capture_backend_dump(app: AppHandle) -> Result<String, String> {
+    let dir = dumps_dir(&app).ok_or("Impossible de résoudre app_data_dir")?;
+    let partial = dir.join(".dump.partial");
+    let result = capture(&app, &dir, &partial);
+    let _ = std::fs::remove_file(&partial);
+    result.map(|path| path.display().to_string())
+}
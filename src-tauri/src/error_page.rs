@@ -0,0 +1,227 @@
+// ---------------------------------------------------------------------------
+// Error page injected into the main webview when the backend is unavailable
+// ---------------------------------------------------------------------------
+
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, Runtime, Url, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{i18n, logs};
+
+/// Host of the action links of the error page. The page is a `data:` URI,
+/// which may not use IPC, so its buttons navigate to this reserved (never
+/// resolving) host and the main window's navigation handler runs the action.
+const ACTION_HOST: &str = "action.tablerreur.invalid";
+
+/// Action of the Enregistrer le diagnostic button on a page without IPC; the
+/// diagnostic travels in the `contents` query parameter.
+pub const SAVE_ACTION: &str = "save-diagnostic";
+
+/// Captured backend log lines appended to a saved diagnostic.
+const SAVED_LOG_LINES: usize = 200;
+
+/// Escape text for inclusion in HTML element content.
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render the full error page: a *title*, an explanatory *message* and a
+/// copyable *diag* block.
+pub fn render(title: &str, message: &str, diag: &str) -> String {
+    render_with_actions(title, message, diag, &[])
+}
+
+/// `render`, plus one button per `(label, action)` in *actions*; clicking one
+/// triggers the action handled by `action_of`.
+pub fn render_with_actions(title: &str, message: &str, diag: &str, actions: &[(&str, &str)]) -> String {
+    let title = html_escape(title);
+    let message = html_escape(message);
+    let diag = html_escape(diag);
+    let text = i18n::strings();
+    let (lang, page_title) = (text.lang, text.error_page_title);
+    let copy_label = html_escape(text.copy_diag);
+    let save_label = html_escape(text.save_diag);
+    let copied = serde_json::to_string(text.copied).unwrap_or_default();
+    let saved_to = serde_json::to_string(text.saved_to).unwrap_or_default();
+    let note = html_escape(text.contact_support);
+    let buttons: String = actions
+        .iter()
+        .map(|(label, action)| {
+            format!(
+                r#"<button class="action" onclick="location.href='https://{ACTION_HOST}/{action}'">{}</button>"#,
+                html_escape(label)
+            )
+        })
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{page_title}</title>
+  <style>
+    *,*::before,*::after{{box-sizing:border-box;margin:0;padding:0}}
+    html,body{{height:100%;background:#fff8f8;font-family:-apple-system,BlinkMacSystemFont,"Segoe UI",system-ui,sans-serif;-webkit-font-smoothing:antialiased}}
+    body{{display:flex;flex-direction:column;justify-content:center;align-items:center;min-height:100vh;padding:2rem}}
+    .card{{max-width:560px;width:100%}}
+    h1{{font-size:1.5rem;font-weight:700;color:#dc2626;margin-bottom:1rem}}
+    p{{color:#475569;line-height:1.6;margin-bottom:1rem}}
+    pre{{background:#f1f5f9;border:1px solid #e2e8f0;border-radius:6px;padding:1rem;font-size:.85rem;white-space:pre-wrap;user-select:all;color:#334155;margin-bottom:1rem}}
+    button{{background:#2563eb;color:#fff;border:none;border-radius:6px;padding:.5rem 1rem;font-size:.875rem;cursor:pointer;font-family:inherit}}
+    button:hover{{background:#1d4ed8}}
+    button.action{{margin-left:.5rem;background:#475569}}
+    button.action:hover{{background:#334155}}
+    .note{{margin-top:1rem;font-size:.8rem;color:#94a3b8}}
+  </style>
+</head>
+<body>
+  <div class="card">
+    <h1>{title}</h1>
+    <p>{message}</p>
+    <pre id="diag">{diag}</pre>
+    <button id="copy-btn" onclick="copyDiag()">{copy_label}</button>
+    <button id="save-btn" class="action" onclick="saveDiag()">{save_label}</button>{buttons}
+    <p class="note" id="save-status" hidden></p>
+    <p class="note">{note}</p>
+  </div>
+  <script>
+    function copyDiag() {{
+      var t = document.getElementById('diag').textContent;
+      var btn = document.getElementById('copy-btn');
+      if (navigator.clipboard) {{
+        navigator.clipboard.writeText(t).catch(function() {{ fallbackCopy(t); }});
+      }} else {{
+        fallbackCopy(t);
+      }}
+      btn.textContent = {copied};
+    }}
+    function saveDiag() {{
+      var t = document.getElementById('diag').textContent;
+      var core = window.__TAURI__ && window.__TAURI__.core;
+      if (core) {{
+        core.invoke('save_diagnostic', {{ contents: t }})
+          .then(function(path) {{ if (path) diagSaved({saved_to} + ' ' + path); }})
+          .catch(function(e) {{ diagSaved(String(e)); }});
+      }} else {{
+        location.href = 'https://{ACTION_HOST}/{SAVE_ACTION}?contents=' + encodeURIComponent(t);
+      }}
+    }}
+    function diagSaved(message) {{
+      var s = document.getElementById('save-status');
+      s.textContent = message;
+      s.hidden = false;
+    }}
+    function fallbackCopy(text) {{
+      var ta = document.createElement('textarea');
+      ta.value = text;
+      document.body.appendChild(ta);
+      ta.select();
+      document.execCommand('copy');
+      document.body.removeChild(ta);
+    }}
+  </script>
+</body>
+</html>"#
+    )
+}
+
+/// Action requested by an error page button, if *url* is one of its links.
+pub fn action_of(url: &Url) -> Option<String> {
+    (url.host_str() == Some(ACTION_HOST)).then(|| url.path().trim_matches('/').to_string())
+}
+
+/// The `contents` parameter of a save action link.
+pub fn save_contents(url: &Url) -> Option<String> {
+    url.query_pairs().find(|(key, _)| key == "contents").map(|(_, value)| value.into_owned())
+}
+
+/// Ask for a destination and write *contents* there, followed by the tail of
+/// the captured backend log. `None` when the user cancels the dialog.
+fn save<R: Runtime>(app: &AppHandle<R>, contents: &str) -> Result<Option<PathBuf>, String> {
+    let text = i18n::strings();
+    let name = format!("tablerreur-diagnostic-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let Some(file) = app
+        .dialog()
+        .file()
+        .set_title(text.save_diag)
+        .set_file_name(name)
+        .add_filter("Texte", &["txt"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = file.into_path().map_err(|e| e.to_string())?;
+    let mut report = format!("{}\n\n--- {} ---\n", contents.trim_end(), text.diag_server_log);
+    for line in logs::tail(app, SAVED_LOG_LINES) {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    std::fs::write(&path, report).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// Save action of a page without IPC: the outcome is written into the page.
+/// Blocking on the dialog: call it off the UI thread.
+pub fn save_from_page<R: Runtime>(app: &AppHandle<R>, contents: &str) {
+    let message = match save(app, contents) {
+        Ok(Some(path)) => format!("{} {}", i18n::strings().saved_to, path.display()),
+        Ok(None) => return,
+        Err(e) => e,
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let message = serde_json::to_string(&message).unwrap_or_default();
+        let _ = window.eval(format!("if(window.diagSaved)diagSaved({message});"));
+    }
+}
+
+/// Write the diagnostic shown on the error page, plus the recent backend
+/// log, to a file chosen by the user. Returns the saved path, or an empty
+/// string when the dialog was cancelled.
+#[tauri::command]
+pub async fn save_diagnostic(app: AppHandle, contents: String) -> Result<String, String> {
+    save(&app, &contents).map(|path| path.map(|p| p.display().to_string()).unwrap_or_default())
+}
+
+/// Replace the content of *window* with the error page.
+pub fn show<R: Runtime>(window: &WebviewWindow<R>, title: &str, message: &str, diag: &str) {
+    let html = render(title, message, diag);
+    // serde_json::to_string encodes the HTML as a JSON string literal
+    // (handles quotes, backslashes, newlines) so it can be passed safely
+    // to document.write().
+    let json_html = serde_json::to_string(&html)
+        .unwrap_or_else(|_| "\"Erreur de démarrage\"".to_string());
+    let js = format!("document.open();document.write({json_html});document.close();");
+    let _ = window.eval(&js);
+}
+
+/// Show (`Some`) or remove (`None`) a non-blocking banner at the top of the
+/// page currently displayed in *window*.
+pub fn set_banner<R: Runtime>(window: &WebviewWindow<R>, text: Option<&str>) {
+    let js = match text {
+        Some(text) => {
+            let text = serde_json::to_string(text).unwrap_or_default();
+            format!(
+                "(function(){{var b=document.getElementById('tablerreur-banner');\
+                 if(!b){{b=document.createElement('div');b.id='tablerreur-banner';\
+                 b.style.cssText='position:fixed;top:0;left:0;right:0;z-index:2147483647;\
+                 padding:.5rem 1rem;background:#f59e0b;color:#1f2937;text-align:center;\
+                 font:500 .875rem system-ui,sans-serif';\
+                 document.body.appendChild(b);}}b.textContent={text};}})();"
+            )
+        }
+        None => "var b=document.getElementById('tablerreur-banner');if(b)b.remove();".to_string(),
+    };
+    let _ = window.eval(&js);
+}
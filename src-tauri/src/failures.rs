@@ -0,0 +1,112 @@
+// ---------------------------------------------------------------------------
+// Persistent startup failures: stop retrying a backend that never starts
+// ---------------------------------------------------------------------------
+//
+// Each failed startup (spawn error or health timeout) is recorded with its
+// time in startup-failures.json. After MAX_FAILURES within FAILURE_WINDOW the
+// next launch goes straight to an error page offering to retry or to reset
+// the configuration, instead of starting the backend yet again. A successful
+// start clears the record.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{i18n, instance, settings};
+
+const FAILURES_FILE: &str = "startup-failures.json";
+
+/// Failed startups, within the window, that suspend further attempts.
+const MAX_FAILURES: usize = 3;
+
+const FAILURE_WINDOW: chrono::Duration = chrono::Duration::hours(24);
+
+/// Buttons of the persistent failure page, as `(label, action)`.
+pub fn actions() -> [(&'static str, &'static str); 2] {
+    let text = i18n::strings();
+    [(text.retry, "retry-startup"), (text.reset_config, "reset-config")]
+}
+
+fn failures_path<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(FAILURES_FILE))
+}
+
+/// Failure times still inside the window, oldest first.
+fn recent<R: Runtime>(app: &impl Manager<R>) -> Vec<DateTime<Local>> {
+    let cutoff = Local::now() - FAILURE_WINDOW;
+    failures_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str::<Vec<String>>(&text).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&Local))
+        .filter(|at| *at > cutoff)
+        .collect()
+}
+
+/// Record a failed startup.
+pub fn record<R: Runtime>(app: &impl Manager<R>) {
+    let Some(path) = failures_path(app) else {
+        return;
+    };
+    let mut failures: Vec<String> = recent(app).iter().map(DateTime::to_rfc3339).collect();
+    failures.push(Local::now().to_rfc3339());
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&failures) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Forget past failures, after a successful start or an explicit retry.
+pub fn clear<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(path) = failures_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Diagnostic text when startups keep failing and should not be retried.
+pub fn persistent<R: Runtime>(app: &impl Manager<R>) -> Option<String> {
+    let failures = recent(app);
+    if failures.len() < MAX_FAILURES {
+        return None;
+    }
+    let times: Vec<String> = failures.iter().map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string()).collect();
+    Some(format!(
+        "Échecs de démarrage ({} sur les dernières {} heures) :\n{}",
+        failures.len(),
+        FAILURE_WINDOW.num_hours(),
+        times.join("\n")
+    ))
+}
+
+/// Move settings.json aside (kept as settings.json.bak) so defaults apply.
+fn reset_config<R: Runtime>(app: &impl Manager<R>) {
+    if let Some(path) = settings::settings_path(app) {
+        let _ = std::fs::rename(&path, path.with_extension("json.bak"));
+    }
+}
+
+/// Run an action of the persistent failure page; both relaunch the app.
+/// Ignored unless the error page is showing, so the backend's own pages
+/// cannot trigger it.
+pub fn run_action<R: Runtime>(app: &AppHandle<R>, action: &str) {
+    let on_error_page = app
+        .get_webview_window("main")
+        .and_then(|window| window.url().ok())
+        .is_some_and(|url| url.scheme() == "data");
+    if !on_error_page {
+        return;
+    }
+    match action {
+        "reset-config" => reset_config(app),
+        "retry-startup" => {}
+        _ => return,
+    }
+    clear(app);
+    instance::release();
+    app.restart();
+}
@@ -0,0 +1,100 @@
+// ---------------------------------------------------------------------------
+// Main window geometry, saved on close and restored at launch
+// ---------------------------------------------------------------------------
+//
+// Stored in physical pixels in `window-state.json` next to the settings. A
+// position that no longer falls on any monitor (an external display was
+// unplugged) is dropped: the window is centered at its default size. A
+// maximized window is saved as such, with the size and position it had
+// before, so that it comes back maximized and un-maximizes to them.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+const STATE_FILE: &str = "window-state.json";
+
+/// Part of the window, from its top-left corner, that must be on a monitor
+/// for the saved position to be reused: enough to grab the title bar.
+const MIN_VISIBLE: (i32, i32) = (100, 40);
+
+/// How far past a monitor's top-left edge the window may start: Windows puts
+/// the invisible resize borders of a maximized or snapped window there.
+const EDGE_MARGIN: i32 = 16;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Geometry {
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    maximized: bool,
+}
+
+fn state_path<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE))
+}
+
+fn load<R: Runtime>(app: &impl Manager<R>) -> Option<Geometry> {
+    state_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str::<Geometry>(&text).ok())
+}
+
+/// Whether the title bar area of *geometry* lies on one of the monitors.
+fn on_screen<R: Runtime>(window: &WebviewWindow<R>, geometry: &Geometry) -> bool {
+    let monitors = window.available_monitors().unwrap_or_default();
+    monitors.iter().any(|monitor| {
+        let (pos, size) = (monitor.position(), monitor.size());
+        let (right, bottom) = (pos.x + size.width as i32, pos.y + size.height as i32);
+        geometry.x >= pos.x - EDGE_MARGIN
+            && geometry.y >= pos.y - EDGE_MARGIN
+            && geometry.x + MIN_VISIBLE.0 <= right
+            && geometry.y + MIN_VISIBLE.1 <= bottom
+    })
+}
+
+/// Apply the saved geometry to *window*, if there is one.
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(geometry) = load(window) else {
+        return;
+    };
+    if geometry.width == 0 || geometry.height == 0 {
+        return;
+    }
+    if on_screen(window, &geometry) {
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+        let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    } else {
+        let _ = window.center();
+    }
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Save the current geometry of *window*. A minimized window keeps the last
+/// saved state: its reported position is meaningless. A maximized one keeps
+/// the last saved size and position, if any, and is only flagged maximized.
+pub fn save<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = match load(window) {
+        Some(saved) if maximized => Geometry { maximized, ..saved },
+        _ => {
+            let size = window.inner_size().map_err(|e| e.to_string())?;
+            let position = window.outer_position().map_err(|e| e.to_string())?;
+            Geometry { width: size.width, height: size.height, x: position.x, y: position.y, maximized }
+        }
+    };
+    let path = state_path(window).ok_or("Impossible de résoudre app_config_dir")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&geometry).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))
+}
@@ -0,0 +1,214 @@
+// ---------------------------------------------------------------------------
+// GPU selection for the webview on hybrid-graphics machines
+// ---------------------------------------------------------------------------
+//
+// The preference is a process-level hint, so it must be in place before the
+// main window (and its webview) is created; changing it takes effect at the
+// next launch. Support per platform:
+//   - Windows : WebView2 browser args `--force-high-performance-gpu` /
+//               `--force-low-power-gpu`.
+//   - Linux   : Mesa `DRI_PRIME`, unless the user already set it.
+//   - macOS   : none at runtime (Info.plist `NSSupportsAutomaticGraphicsSwitching`),
+//               the preference is only reported.
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+use crate::settings;
+
+/// How long `gpu_status` waits for the webview to report its WebGL renderer.
+const RENDERER_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuPreference {
+    /// Let the OS and the engine decide.
+    #[default]
+    Auto,
+    /// Ask for the discrete GPU.
+    HighPerformance,
+    /// Ask for the integrated GPU.
+    LowPower,
+}
+
+/// Preference in force for this process, and the last renderer string the
+/// webview reported.
+pub struct GpuState {
+    pub applied: GpuPreference,
+    pub renderer: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuReport {
+    /// Graphics adapters found on the machine.
+    pub adapters: Vec<String>,
+    /// WebGL renderer seen by the webview, `None` if it could not report it.
+    pub renderer: Option<String>,
+    /// Preference saved in the settings.
+    pub preference: GpuPreference,
+    /// Preference actually applied when this process started.
+    pub applied: GpuPreference,
+    /// Whether this platform honours the preference at all.
+    pub supported: bool,
+}
+
+const SUPPORTED: bool = cfg!(any(target_os = "windows", target_os = "linux"));
+
+/// Builder for the `main` window from `tauri.conf.json`, with the GPU hint
+/// for *preference* applied. Must run before any webview exists.
+pub fn main_window_builder<R: Runtime>(
+    app: &AppHandle<R>,
+    preference: GpuPreference,
+) -> tauri::Result<WebviewWindowBuilder<'_, R, AppHandle<R>>> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned()
+        .unwrap_or_default();
+    #[allow(unused_mut)]
+    let mut builder = WebviewWindowBuilder::from_config(app, &config)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        // Replacing the browser args drops wry's defaults, so keep them.
+        let mut args =
+            String::from("--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection");
+        match preference {
+            GpuPreference::HighPerformance => args.push_str(" --force-high-performance-gpu"),
+            GpuPreference::LowPower => args.push_str(" --force-low-power-gpu"),
+            GpuPreference::Auto => {}
+        }
+        builder = builder.additional_browser_args(&args);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let prime = match preference {
+            GpuPreference::HighPerformance => Some("1"),
+            GpuPreference::LowPower => Some("0"),
+            GpuPreference::Auto => None,
+        };
+        // The WebKit web process inherits the environment when it spawns.
+        if let (Some(value), None) = (prime, std::env::var_os("DRI_PRIME")) {
+            std::env::set_var("DRI_PRIME", value);
+        }
+    }
+    #[cfg(target_os = "macos")]
+    let _ = preference;
+
+    Ok(builder)
+}
+
+fn command(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Names of the graphics adapters, best effort.
+fn adapters() -> Vec<String> {
+    #[cfg(target_os = "windows")]
+    let names = command(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name }",
+        ],
+    )
+    .map(|out| out.lines().map(|l| l.trim().to_string()).collect::<Vec<_>>());
+
+    #[cfg(target_os = "macos")]
+    let names = command("system_profiler", &["SPDisplaysDataType"]).map(|out| {
+        out.lines()
+            .filter_map(|l| l.trim().strip_prefix("Chipset Model:"))
+            .map(|name| name.trim().to_string())
+            .collect::<Vec<_>>()
+    });
+
+    #[cfg(target_os = "linux")]
+    let names = command("lspci", &[]).map(|out| {
+        out.lines()
+            .filter_map(|l| {
+                ["VGA compatible controller: ", "3D controller: ", "Display controller: "]
+                    .iter()
+                    .find_map(|kind| l.split_once(kind).map(|(_, name)| name.trim().to_string()))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    names.unwrap_or_default().into_iter().filter(|n| !n.is_empty()).collect()
+}
+
+/// Ask the page to post its WebGL renderer back through `report_webview_gpu`.
+/// Only works once the window shows the backend (IPC is not allowed on
+/// `data:` pages such as the splash or error screens).
+const RENDERER_PROBE_JS: &str = r#"(function () {
+  try {
+    var gl = document.createElement('canvas').getContext('webgl');
+    var renderer = null;
+    if (gl) {
+      var ext = gl.getExtension('WEBGL_debug_renderer_info');
+      renderer = gl.getParameter(ext ? ext.UNMASKED_RENDERER_WEBGL : gl.RENDERER);
+    }
+    window.__TAURI__.core.invoke('report_webview_gpu', { renderer: renderer });
+  } catch (_) {}
+})();"#;
+
+fn renderer<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.try_state::<GpuState>()?.renderer.lock().ok()?.clone()
+}
+
+/// Adapters present, renderer used by the webview and the GPU preference.
+#[tauri::command]
+pub async fn gpu_status(app: AppHandle) -> GpuReport {
+    if let Some(state) = app.try_state::<GpuState>() {
+        if let Ok(mut guard) = state.renderer.lock() {
+            *guard = None;
+        }
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.eval(RENDERER_PROBE_JS);
+    }
+    let deadline = Instant::now() + RENDERER_TIMEOUT;
+    let adapters = adapters();
+    while renderer(&app).is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    GpuReport {
+        adapters,
+        renderer: renderer(&app),
+        preference: settings::current(&app).gpu_preference,
+        applied: app.try_state::<GpuState>().map(|s| s.applied).unwrap_or_default(),
+        supported: SUPPORTED,
+    }
+}
+
+/// Callback of the renderer probe injected by `gpu_status`.
+#[tauri::command]
+pub fn report_webview_gpu(app: AppHandle, renderer: Option<String>) {
+    if let Some(state) = app.try_state::<GpuState>() {
+        if let Ok(mut guard) = state.renderer.lock() {
+            *guard = renderer;
+        }
+    }
+}
+
+/// Save the GPU preference; it applies from the next launch.
+#[tauri::command]
+pub fn set_gpu_preference(app: AppHandle, preference: GpuPreference) -> Result<GpuPreference, String> {
+    settings::update(&app, |s| s.gpu_preference = preference).map(|s| s.gpu_preference)
+}
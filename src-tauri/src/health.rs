@@ -0,0 +1,360 @@
+// ---------------------------------------------------------------------------
+// Backend health: startup polling and the post-start monitor
+// ---------------------------------------------------------------------------
+
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::{backend, error_page, heartbeat, http, i18n, logs, settings};
+
+/// Path polled to decide whether the backend can serve requests.
+pub const HEALTH_PATH: &str = "/health";
+
+/// Interval between two probes of the health monitor.
+const MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Interval between probes while the backend is failing.
+const DOWN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a single monitor probe may wait for the HTTP response.
+const MONITOR_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a single startup probe may take, connect and response included.
+const STARTUP_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Startup polling: every FAST_POLL during FAST_PHASE, which covers a warm
+/// start, then doubling up to MAX_POLL so a cold start is not hammered.
+const FAST_POLL: Duration = Duration::from_millis(50);
+const FAST_PHASE: Duration = Duration::from_secs(1);
+const MAX_POLL: Duration = Duration::from_millis(500);
+
+/// Divergence between wall-clock and monotonic time, over one polling
+/// iteration, taken as a suspend/resume or clock jump.
+const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(2);
+
+/// Number of monitor probes kept for diagnostics.
+const PROBE_HISTORY_LEN: usize = 50;
+
+/// Outcome of a single HTTP health probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Probe {
+    /// `GET /health` answered 200.
+    Healthy,
+    /// The backend answered, but not with 200 (or with garbage).
+    BadStatus,
+    /// TCP connected but no HTTP response arrived in time.
+    TimedOut,
+    /// Nothing accepts connections on the port.
+    Unreachable,
+}
+
+/// One probe of the health monitor, kept for the support package.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeRecord {
+    pub at: String,
+    pub port: u16,
+    pub probe: Probe,
+    pub latency_ms: u64,
+}
+
+/// The last PROBE_HISTORY_LEN monitor probes, oldest first.
+#[derive(Default)]
+pub struct ProbeHistory(pub Mutex<VecDeque<ProbeRecord>>);
+
+fn record_probe<R: Runtime>(app: &AppHandle<R>, record: ProbeRecord) {
+    if let Some(history) = app.try_state::<ProbeHistory>() {
+        if let Ok(mut guard) = history.0.lock() {
+            if guard.len() == PROBE_HISTORY_LEN {
+                guard.pop_front();
+            }
+            guard.push_back(record);
+        }
+    }
+}
+
+/// Snapshot of the recorded monitor probes.
+pub fn probe_history<R: Runtime>(app: &AppHandle<R>) -> Vec<ProbeRecord> {
+    app.try_state::<ProbeHistory>()
+        .and_then(|history| history.0.lock().ok().map(|h| h.iter().cloned().collect()))
+        .unwrap_or_default()
+}
+
+/// Whether something accepts TCP on 127.0.0.1:{port} and on [::1]:{port}.
+/// Both families are probed concurrently so the call takes at most *timeout*.
+pub fn probe_dual_stack(port: u16, timeout: Duration) -> (bool, bool) {
+    let accepts = move |addr: SocketAddr| TcpStream::connect_timeout(&addr, timeout).is_ok();
+    let v6 = std::thread::spawn(move || accepts(SocketAddr::from((Ipv6Addr::LOCALHOST, port))));
+    let v4 = accepts(SocketAddr::from((Ipv4Addr::LOCALHOST, port)));
+    (v4, v6.join().unwrap_or(false))
+}
+
+/// Probe `GET /health` on 127.0.0.1:{port}, telling apart a refused connection
+/// from a backend that accepts TCP but never answers.
+pub fn probe(port: u16, timeout: Duration) -> Probe {
+    let stream = match http::connect(port, timeout) {
+        Ok(stream) => stream,
+        Err(_) => return Probe::Unreachable,
+    };
+    match http::send(stream, "GET", HEALTH_PATH, None) {
+        Ok(resp) if resp.status == 200 => Probe::Healthy,
+        Ok(_) => Probe::BadStatus,
+        Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+            Probe::TimedOut
+        }
+        Err(_) => Probe::BadStatus,
+    }
+}
+
+/// Delay before the next startup probe, *waited* into the poll and after a
+/// previous delay of *last*.
+fn next_poll(waited: Duration, last: Duration) -> Duration {
+    if waited < FAST_PHASE {
+        FAST_POLL
+    } else {
+        (last * 2).min(MAX_POLL)
+    }
+}
+
+/// Poll `GET /health` on 127.0.0.1:{port} up to *timeout*, every 50 ms at
+/// first then backing off to 500 ms. Returns true as soon as it answers 200:
+/// the sidecar opens its socket a moment before it can serve, so an accepted
+/// connection is not enough and any other answer means "not ready yet".
+///
+/// When wall-clock and monotonic time drift apart during an iteration (the
+/// machine slept, or the VM clock was frozen then resumed), the jump is
+/// logged and added to the deadline so it does not count against *timeout*.
+pub fn wait_for_health<R: Runtime>(app: &impl Manager<R>, port: u16, timeout: Duration) -> bool {
+    wait_for_health_observed(app, port, timeout, |_, _| {})
+}
+
+/// `wait_for_health`, calling *on_probe* with the outcome and latency of
+/// every attempt (used by the startup trace).
+pub fn wait_for_health_observed<R: Runtime>(
+    app: &impl Manager<R>,
+    port: u16,
+    timeout: Duration,
+    on_probe: impl FnMut(bool, Duration),
+) -> bool {
+    poll_health(port, timeout, on_probe, |note| logs::note(app, note))
+}
+
+/// The polling loop of `wait_for_health`, handing clock jump notes to
+/// *on_clock_jump*.
+fn poll_health(
+    port: u16,
+    timeout: Duration,
+    mut on_probe: impl FnMut(bool, Duration),
+    mut on_clock_jump: impl FnMut(&str),
+) -> bool {
+    let start = Instant::now();
+    let mut deadline = start + timeout;
+    let mut interval = FAST_POLL;
+    let mut last_mono = start;
+    let mut last_wall = SystemTime::now();
+    loop {
+        let started = Instant::now();
+        let ok = probe(port, STARTUP_PROBE_TIMEOUT) == Probe::Healthy;
+        on_probe(ok, started.elapsed());
+        if ok {
+            return true;
+        }
+        let (mono, wall) = (Instant::now(), SystemTime::now());
+        let mono_delta = mono - last_mono;
+        // A wall clock set backwards reads as zero elapsed, which still skews.
+        let wall_delta = wall.duration_since(last_wall).unwrap_or_default();
+        let skew = mono_delta.abs_diff(wall_delta);
+        if skew > CLOCK_SKEW_THRESHOLD {
+            on_clock_jump(&format!(
+                "saut d'horloge de {:.1} s pendant l'attente du port {port} \
+                 (monotone {:.1} s, murale {:.1} s) : échéance prolongée",
+                skew.as_secs_f64(),
+                mono_delta.as_secs_f64(),
+                wall_delta.as_secs_f64()
+            ));
+            deadline += skew;
+        }
+        (last_mono, last_wall) = (mono, wall);
+        if mono >= deadline {
+            return false;
+        }
+        interval = next_poll(mono - start, interval);
+        std::thread::sleep(interval.min(deadline - mono));
+    }
+}
+
+/// Show or remove the reconnect banner and tell the frontend through the
+/// `backend-connection` event.
+fn surface_connection<R: Runtime>(app: &AppHandle<R>, connected: bool) {
+    let _ = app.emit("backend-connection", serde_json::json!({ "connected": connected }));
+    if let Some(window) = app.get_webview_window("main") {
+        let banner = (!connected).then_some(i18n::strings().connection_lost);
+        error_page::set_banner(&window, banner);
+    }
+}
+
+/// Background thread watching the backend once the main window points at it.
+///
+/// A TCP-only check cannot see a backend whose event loop is deadlocked: the
+/// socket still accepts connections, but no HTTP response ever comes. When the
+/// HTTP probe keeps timing out while TCP connects for longer than
+/// `hang_restart_secs`, the backend is considered hung and restarted.
+///
+/// Short blips recover silently: the reconnect banner only appears once the
+/// backend has failed every probe for `reconnect_grace_ms`.
+pub fn start_monitor<R: Runtime>(app: AppHandle<R>) {
+    let heartbeat =
+        heartbeat::register(&app, "health-monitor", MONITOR_INTERVAL + MONITOR_PROBE_TIMEOUT);
+    let _ = std::thread::Builder::new().name("health-monitor".into()).spawn(move || {
+        let mut hung_since: Option<Instant> = None;
+        let mut down_since: Option<Instant> = None;
+        let mut banner_shown = false;
+        loop {
+            if heartbeat.beat() {
+                // Time spent paused must not count towards hang or grace windows.
+                (hung_since, down_since) = (None, None);
+            }
+            // Probe faster while the backend is failing, to time the grace window.
+            let interval = if down_since.is_some() { DOWN_INTERVAL } else { MONITOR_INTERVAL };
+            std::thread::sleep(interval);
+            if backend::is_restarting(&app) {
+                // The restart navigates the window, which drops the banner.
+                (hung_since, down_since, banner_shown) = (None, None, false);
+                continue;
+            }
+            let Some(port) = backend::current_port(&app) else {
+                continue;
+            };
+            let started = Instant::now();
+            let outcome = probe(port, MONITOR_PROBE_TIMEOUT);
+            record_probe(&app, ProbeRecord {
+                at: chrono::Local::now().to_rfc3339(),
+                port,
+                probe: outcome,
+                latency_ms: started.elapsed().as_millis() as u64,
+            });
+            let current = settings::current(&app);
+
+            if outcome == Probe::Healthy {
+                down_since = None;
+                if banner_shown {
+                    banner_shown = false;
+                    surface_connection(&app, true);
+                }
+            } else {
+                let since = *down_since.get_or_insert_with(Instant::now);
+                let grace = Duration::from_millis(current.reconnect_grace_ms);
+                if !banner_shown && since.elapsed() >= grace {
+                    banner_shown = true;
+                    surface_connection(&app, false);
+                }
+            }
+
+            if outcome != Probe::TimedOut {
+                hung_since = None;
+                continue;
+            }
+            let since = *hung_since.get_or_insert_with(Instant::now);
+            let window = Duration::from_secs(current.hang_restart_secs);
+            if since.elapsed() >= window {
+                let silent = since.elapsed().as_secs();
+                logs::note(&app, &format!("serveur sans réponse HTTP depuis {silent}s, redémarrage"));
+                (hung_since, down_since, banner_shown) = (None, None, false);
+                backend::auto_restart(&app);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Free loopback port, released for the test to bind later.
+    fn free_port() -> u16 {
+        TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port()
+    }
+
+    /// Serve `200` on *port* once *delay* has passed.
+    fn serve_after(port: u16, delay: Duration) {
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).unwrap();
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+    }
+
+    /// Probe times, from the start of the poll, that `next_poll` yields over
+    /// *span*, ignoring the time the probes themselves take.
+    fn probe_times(span: Duration) -> Vec<Duration> {
+        let (mut at, mut interval, mut times) = (Duration::ZERO, FAST_POLL, Vec::new());
+        while at < span {
+            times.push(at);
+            interval = next_poll(at, interval);
+            at += interval;
+        }
+        times
+    }
+
+    #[test]
+    fn polls_fast_then_backs_off() {
+        assert_eq!(next_poll(Duration::ZERO, FAST_POLL), FAST_POLL);
+        assert_eq!(next_poll(Duration::from_millis(900), FAST_POLL), FAST_POLL);
+        let mut interval = FAST_POLL;
+        let mut schedule = Vec::new();
+        for _ in 0..5 {
+            interval = next_poll(FAST_PHASE, interval);
+            schedule.push(interval.as_millis());
+        }
+        assert_eq!(schedule, [100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn a_warm_start_is_seen_within_one_fast_poll() {
+        let times = probe_times(FAST_PHASE);
+        assert_eq!(times.len(), 20);
+        // A backend ready at any point of the fast phase is probed at most
+        // FAST_POLL later, where the former fixed poll took up to 200 ms.
+        assert!(times.windows(2).all(|pair| pair[1] - pair[0] == FAST_POLL));
+    }
+
+    #[test]
+    fn a_cold_start_is_probed_at_least_every_max_poll() {
+        let times = probe_times(Duration::from_secs(30));
+        assert!(times.windows(2).all(|pair| pair[1] - pair[0] <= MAX_POLL));
+        // Backed off: about two probes a second once past the fast phase.
+        let late = times.iter().filter(|at| **at >= Duration::from_secs(10)).count();
+        assert!((40..=41).contains(&late), "{late}");
+    }
+
+    #[test]
+    fn returns_once_the_backend_opens() {
+        let port = free_port();
+        serve_after(port, Duration::from_millis(300));
+        assert!(poll_health(port, Duration::from_secs(30), |_, _| {}, |_| {}));
+    }
+
+    #[test]
+    fn keeps_the_deadline() {
+        let port = free_port();
+        let started = Instant::now();
+        assert!(!poll_health(port, Duration::from_millis(1500), |_, _| {}, |_| {}));
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(1500), "{waited:?}");
+        // Generous: the last sleep is cut at the deadline, but a loaded test
+        // machine can still hold the thread for a while.
+        assert!(waited < Duration::from_secs(10), "{waited:?}");
+    }
+}
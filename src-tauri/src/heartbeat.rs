@@ -0,0 +1,147 @@
+// ---------------------------------------------------------------------------
+// Heartbeats of the background threads, for hang diagnosis
+// ---------------------------------------------------------------------------
+//
+// Each long-lived thread registers itself and bumps its heartbeat on every
+// loop iteration. A heartbeat much older than the thread's interval points at
+// the thread that is stuck.
+//
+// The heartbeat is also the single gate through which `pause_monitors` stops
+// every periodic loop during a profiling run. Both commands exist in debug
+// builds only, like the `chaos` hooks. The log drains are not gated:
+// they only react to backend output, and stalling them would block the
+// backend on a full pipe.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(debug_assertions)]
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// A heartbeat is stale after this many missed intervals.
+#[cfg(debug_assertions)]
+const STALE_AFTER_INTERVALS: u32 = 3;
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Set while the periodic threads are paused for profiling.
+static PAUSED: Mutex<bool> = Mutex::new(false);
+static RESUMED: Condvar = Condvar::new();
+
+#[cfg(debug_assertions)]
+fn is_paused() -> bool {
+    PAUSED.lock().map(|p| *p).unwrap_or(false)
+}
+
+/// Handle kept by a thread to report that it is alive.
+#[derive(Clone)]
+pub struct Heartbeat(Arc<AtomicU64>);
+
+impl Heartbeat {
+    /// Report the thread alive, first blocking while monitors are paused.
+    /// Returns true if it was paused, so the caller can drop timing state
+    /// that the pause made meaningless.
+    pub fn beat(&self) -> bool {
+        let mut waited = false;
+        if let Ok(mut paused) = PAUSED.lock() {
+            while *paused {
+                waited = true;
+                paused = match RESUMED.wait(paused) {
+                    Ok(guard) => guard,
+                    Err(_) => break,
+                };
+            }
+        }
+        self.0.store(now_ms(), Ordering::Relaxed);
+        waited
+    }
+}
+
+// Only read by `runtime_state`, which release builds do not have.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+struct Entry {
+    last_ms: Arc<AtomicU64>,
+    interval: Duration,
+}
+
+/// Managed registry of thread heartbeats, keyed by thread name.
+#[derive(Default)]
+pub struct Heartbeats(Mutex<BTreeMap<&'static str, Entry>>);
+
+/// Register the thread *name*, expected to beat every *interval*.
+pub fn register<R: Runtime>(app: &AppHandle<R>, name: &'static str, interval: Duration) -> Heartbeat {
+    let last_ms = Arc::new(AtomicU64::new(now_ms()));
+    if let Some(registry) = app.try_state::<Heartbeats>() {
+        if let Ok(mut guard) = registry.0.lock() {
+            guard.insert(name, Entry { last_ms: last_ms.clone(), interval });
+        }
+    }
+    Heartbeat(last_ms)
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadState {
+    pub name: &'static str,
+    /// Unix time of the last heartbeat, in milliseconds.
+    pub last_heartbeat_ms: u64,
+    pub age_ms: u64,
+    pub interval_ms: u64,
+    pub stale: bool,
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeState {
+    pub now_ms: u64,
+    /// Periodic threads are held by `pause_monitors`; none is reported stale.
+    pub paused: bool,
+    pub threads: Vec<ThreadState>,
+}
+
+/// Report each background thread with the age of its last heartbeat.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub fn runtime_state(app: AppHandle) -> RuntimeState {
+    let now = now_ms();
+    let paused = is_paused();
+    let threads = app
+        .state::<Heartbeats>()
+        .0
+        .lock()
+        .map(|guard| {
+            guard
+                .iter()
+                .map(|(name, entry)| {
+                    let last = entry.last_ms.load(Ordering::Relaxed);
+                    let age_ms = now.saturating_sub(last);
+                    let interval_ms = entry.interval.as_millis() as u64;
+                    ThreadState {
+                        name,
+                        last_heartbeat_ms: last,
+                        age_ms,
+                        interval_ms,
+                        stale: !paused && age_ms > interval_ms * STALE_AFTER_INTERVALS as u64,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    RuntimeState { now_ms: now, paused, threads }
+}
+
+/// Suspend (`true`) or resume (`false`) the periodic background threads, so
+/// that a profiling run sees only backend activity.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub fn pause_monitors(enabled: bool) -> Result<bool, String> {
+    let mut paused = PAUSED.lock().map_err(|e| e.to_string())?;
+    *paused = enabled;
+    RESUMED.notify_all();
+    Ok(enabled)
+}
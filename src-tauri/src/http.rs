@@ -0,0 +1,189 @@
+// ---------------------------------------------------------------------------
+// Minimal HTTP/1.1 client for the loopback backend (std only)
+// ---------------------------------------------------------------------------
+//
+// The backend only ever listens on 127.0.0.1, so a plain TcpStream with
+// `Connection: close` is enough — this avoids pulling in an HTTP crate.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// Status code and raw body of a backend response.
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Parse the status code out of a line such as `HTTP/1.1 200 OK`.
+pub fn parse_status_line(line: &str) -> Option<u16> {
+    let mut parts = line.split_whitespace();
+    let version = parts.next()?;
+    if !version.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+/// Connect to 127.0.0.1:{port} with *timeout* applied to connect, read and write.
+pub fn connect(port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    Ok(stream)
+}
+
+/// Write one request on *stream*.
+fn write_request(
+    stream: &mut TcpStream,
+    method: &str,
+    path: &str,
+    body: Option<&[u8]>,
+) -> io::Result<()> {
+    let port = stream.peer_addr().map(|a| a.port()).unwrap_or(0);
+    let mut head = format!(
+        "{method} {path} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nConnection: close\r\n"
+    );
+    if let Some(body) = body {
+        head.push_str(&format!(
+            "Content-Type: application/json\r\nContent-Length: {}\r\n",
+            body.len()
+        ));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if let Some(body) = body {
+        stream.write_all(body)?;
+    }
+    stream.flush()
+}
+
+/// Send one request over an already connected *stream* and read the whole response.
+pub fn send(
+    mut stream: TcpStream,
+    method: &str,
+    path: &str,
+    body: Option<&[u8]>,
+) -> io::Result<Response> {
+    write_request(&mut stream, method, path, body)?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_response(&raw)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "réponse HTTP invalide"))
+}
+
+/// Split a raw response into status and body.
+fn parse_response(raw: &[u8]) -> Option<Response> {
+    let header_end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&raw[..header_end]).ok()?;
+    let status = parse_status_line(head.lines().next()?)?;
+    Some(Response { status, body: raw[header_end + 4..].to_vec() })
+}
+
+/// `GET {path}` on the backend.
+pub fn get(port: u16, path: &str, timeout: Duration) -> io::Result<Response> {
+    send(connect(port, timeout)?, "GET", path, None)
+}
+
+/// `POST {path}` on the backend with a JSON *body*.
+pub fn post(port: u16, path: &str, body: &[u8], timeout: Duration) -> io::Result<Response> {
+    send(connect(port, timeout)?, "POST", path, Some(body))
+}
+
+// ---------------------------------------------------------------------------
+// Streaming: large bodies go straight to a writer instead of memory
+// ---------------------------------------------------------------------------
+
+/// Status and headers of a streamed response.
+pub struct Head {
+    pub status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl Head {
+    /// Value of the header *name* (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_head(reader: &mut impl BufRead) -> io::Result<Head> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status = parse_status_line(&line).ok_or_else(|| invalid("réponse HTTP invalide"))?;
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid("en-têtes HTTP tronqués"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(Head { status, headers });
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+}
+
+/// Copy a `Transfer-Encoding: chunked` body from *reader* to *out*.
+fn copy_chunked(reader: &mut impl BufRead, out: &mut impl Write) -> io::Result<u64> {
+    let mut total = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = u64::from_str_radix(size, 16).map_err(|_| invalid("bloc HTTP invalide"))?;
+        if size == 0 {
+            return Ok(total);
+        }
+        let copied = io::copy(&mut reader.by_ref().take(size), out)?;
+        if copied != size {
+            return Err(invalid("bloc HTTP tronqué"));
+        }
+        total += copied;
+        line.clear();
+        reader.read_line(&mut line)?; // CRLF after the chunk
+    }
+}
+
+/// Send a request and stream the response body into *out*, whatever its
+/// size. Returns the head and the number of body bytes written.
+pub fn stream_to(
+    port: u16,
+    method: &str,
+    path: &str,
+    body: Option<&[u8]>,
+    timeout: Duration,
+    out: &mut impl Write,
+) -> io::Result<(Head, u64)> {
+    let mut stream = connect(port, timeout)?;
+    write_request(&mut stream, method, path, body)?;
+    let mut reader = BufReader::new(stream);
+    let head = read_head(&mut reader)?;
+    let chunked = head.header("Transfer-Encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked"));
+    let length = head.header("Content-Length").and_then(|v| v.parse::<u64>().ok());
+    let written = match (chunked, length) {
+        (true, _) => copy_chunked(&mut reader, out)?,
+        (false, Some(len)) => io::copy(&mut reader.take(len), out)?,
+        (false, None) => io::copy(&mut reader, out)?,
+    };
+    out.flush()?;
+    Ok((head, written))
+}
@@ -0,0 +1,403 @@
+// ---------------------------------------------------------------------------
+// UI strings of the shell: menus, tray, dialogs and error pages
+// ---------------------------------------------------------------------------
+//
+// The language comes from the standard locale variables (`LC_ALL`,
+// `LC_MESSAGES`, `LANG`), read once per launch. French stays the default:
+// anything that is not recognisably English, including an unset or `C`
+// locale, gets the French table.
+
+use std::sync::OnceLock;
+
+pub struct Strings {
+    /// Value of the `lang` attribute of generated pages.
+    pub lang: &'static str,
+
+    // Menus
+    pub menu_file: &'static str,
+    pub menu_open_workspace: &'static str,
+    pub menu_recent: &'static str,
+    pub menu_no_recent: &'static str,
+    pub menu_reload: &'static str,
+    pub menu_restart_backend: &'static str,
+    pub menu_quit: &'static str,
+    pub menu_help: &'static str,
+    pub menu_check_updates: &'static str,
+    pub menu_support_package: &'static str,
+    pub menu_about: &'static str,
+    pub tray_show: &'static str,
+    pub tray_hide: &'static str,
+    pub splash_starting: &'static str,
+
+    // Quit confirmation
+    pub quit_unsaved_title: &'static str,
+    pub quit_unsaved: &'static str,
+    pub cancel: &'static str,
+
+    // Error page chrome
+    pub error_page_title: &'static str,
+    pub copy_diag: &'static str,
+    pub copied: &'static str,
+    pub save_diag: &'static str,
+    pub saved_to: &'static str,
+    pub contact_support: &'static str,
+
+    // Startup errors
+    pub startup_error: &'static str,
+    pub startup_timeout: &'static str,
+    pub port_not_announced: &'static str,
+    pub backend_missing_title: &'static str,
+    pub backend_missing: &'static str,
+    pub spawn_failed_title: &'static str,
+    pub spawn_failed: &'static str,
+    pub integrity_title: &'static str,
+    pub integrity_failed: &'static str,
+    pub repeated_failures_title: &'static str,
+    pub repeated_failures: &'static str,
+    pub retry: &'static str,
+    pub reset_config: &'static str,
+
+    // Runtime errors
+    pub health_timeout: &'static str,
+    pub backend_stopped_title: &'static str,
+    pub backend_stopped: &'static str,
+    pub restart_error_title: &'static str,
+    pub restart_failed: &'static str,
+    pub unstable_title: &'static str,
+    pub unstable: &'static str,
+
+    // Support package
+    pub support_package_title: &'static str,
+    pub support_package_created: &'static str,
+    pub support_package_attach: &'static str,
+    pub support_package_failed: &'static str,
+
+    // Runtime warnings
+    pub connection_lost: &'static str,
+    pub disk_full_title: &'static str,
+    pub disk_full: &'static str,
+    pub loopback_blocked_title: &'static str,
+    pub loopback_blocked: &'static str,
+    pub server_not_started: &'static str,
+    pub server_unreachable: &'static str,
+
+    // Port holders
+    pub free_port_title: &'static str,
+    pub free_port_prompt: &'static str,
+    pub free_port_process: &'static str,
+    pub terminate: &'static str,
+    pub port_out_of_range: &'static str,
+    pub port_not_held: &'static str,
+    pub port_owned: &'static str,
+    pub terminate_failed: &'static str,
+
+    // Workspaces
+    pub workspace_title: &'static str,
+    pub workspace_filter: &'static str,
+    pub workspace_unsupported: &'static str,
+    pub workspace_refused: &'static str,
+
+    // Updates
+    pub updates_title: &'static str,
+    pub update_available_title: &'static str,
+    pub update_available: &'static str,
+    pub latest_version: &'static str,
+    pub installed_version: &'static str,
+    pub download: &'static str,
+    pub later: &'static str,
+    pub up_to_date: &'static str,
+    pub update_check_failed: &'static str,
+    pub open_releases: &'static str,
+    pub open_page: &'static str,
+    pub close: &'static str,
+    pub copy: &'static str,
+    pub unknown: &'static str,
+    pub curl_unavailable: &'static str,
+    pub request_failed: &'static str,
+    pub unexpected_response: &'static str,
+    pub unreadable_version: &'static str,
+
+    // Concurrency
+    pub at_least_one_worker: &'static str,
+    pub concurrency_unsupported: &'static str,
+    pub concurrency_refused: &'static str,
+
+    // Diagnostic labels
+    pub diag_port: &'static str,
+    pub diag_port_range: &'static str,
+    pub diag_auto_port: &'static str,
+    pub diag_timeout: &'static str,
+    pub diag_seconds: &'static str,
+    pub diag_system: &'static str,
+    pub diag_server_log: &'static str,
+    pub diag_unavailable: &'static str,
+    pub diag_exit_code: &'static str,
+    pub diag_restart_budget: &'static str,
+    pub diag_per_session: &'static str,
+}
+
+const FR: Strings = Strings {
+    lang: "fr",
+
+    menu_file: "Fichier",
+    menu_open_workspace: "Ouvrir un espace de travail…",
+    menu_recent: "Récents",
+    menu_no_recent: "Aucun fichier récent",
+    menu_reload: "Recharger",
+    menu_restart_backend: "Redémarrer le serveur",
+    menu_quit: "Quitter",
+    menu_help: "Aide",
+    menu_check_updates: "Vérifier les mises à jour",
+    menu_support_package: "Créer un paquet de support",
+    menu_about: "À propos de Tablerreur",
+    tray_show: "Afficher",
+    tray_hide: "Masquer",
+    splash_starting: "Démarrage du serveur…",
+
+    quit_unsaved_title: "Quitter sans enregistrer ?",
+    quit_unsaved: "Des modifications n'ont pas été enregistrées et seront perdues.",
+    cancel: "Annuler",
+
+    error_page_title: "Erreur — Tablerreur",
+    copy_diag: "Copier le diagnostic",
+    copied: "✓ Copié",
+    save_diag: "Enregistrer le diagnostic",
+    saved_to: "Diagnostic enregistré :",
+    contact_support: "Contactez le support avec ces informations.",
+
+    startup_error: "Erreur de démarrage",
+    startup_timeout: "Le serveur Tablerreur n'a pas pu démarrer dans les délais.",
+    port_not_announced: "Le serveur Tablerreur n'a pas indiqué sur quel port il écoute.",
+    backend_missing_title: "Serveur introuvable",
+    backend_missing: "Le binaire du serveur Tablerreur est introuvable. Réinstallez l'application.",
+    spawn_failed_title: "Échec du démarrage",
+    spawn_failed: "Le serveur Tablerreur n'a pas pu être lancé.",
+    integrity_title: "Erreur de sécurité",
+    integrity_failed: "Le serveur embarqué a été modifié depuis l'installation. \
+                       Par sécurité, il n'a pas été lancé : réinstallez Tablerreur.",
+    repeated_failures_title: "Échecs de démarrage répétés",
+    repeated_failures: "Le serveur Tablerreur n'a pas pu démarrer lors des derniers lancements. \
+                        Une configuration invalide en est souvent la cause : vous pouvez la \
+                        réinitialiser ou réessayer tel quel.",
+    retry: "Réessayer",
+    reset_config: "Réinitialiser la configuration",
+
+    health_timeout: "Le serveur n'a pas répondu dans les délais.",
+    backend_stopped_title: "Serveur arrêté",
+    backend_stopped: "Le serveur Tablerreur s'est arrêté de manière inattendue.",
+    restart_error_title: "Erreur de redémarrage",
+    restart_failed: "Le serveur Tablerreur n'a pas pu être redémarré.",
+    unstable_title: "Serveur instable",
+    unstable: "Le serveur Tablerreur a dû être redémarré trop de fois pendant cette session. \
+               Les redémarrages automatiques sont suspendus.",
+
+    support_package_title: "Paquet de support",
+    support_package_created: "Paquet de support créé :",
+    support_package_attach: "Joignez ce fichier à votre demande.",
+    support_package_failed: "Impossible de créer le paquet de support :",
+
+    connection_lost: "Connexion au serveur perdue — reconnexion en cours…",
+    disk_full_title: "Espace disque insuffisant",
+    disk_full: "Le disque est plein : Tablerreur a cessé d'écrire ses journaux. \
+                Libérez de l'espace puis relancez l'application.",
+    loopback_blocked_title: "Connexion locale bloquée",
+    loopback_blocked: "Le serveur local répond, mais la fenêtre de Tablerreur ne parvient pas à le joindre.\n\n\
+                       Un proxy ou un pare-feu bloque sans doute les connexions vers 127.0.0.1 : \
+                       ajoutez 127.0.0.1 et localhost aux exceptions du proxy (variable NO_PROXY \
+                       ou réglages proxy du système), ou autorisez Tablerreur dans le pare-feu.",
+    server_not_started: "Serveur non démarré",
+    server_unreachable: "Serveur injoignable :",
+
+    free_port_title: "Libérer le port",
+    free_port_prompt: "Terminer le processus qui occupe ce port ?",
+    free_port_process: "Processus",
+    terminate: "Terminer",
+    port_out_of_range: "Port hors de la plage du serveur :",
+    port_not_held: "Aucun processus n'écoute sur le port",
+    port_owned: "Ce port est utilisé par le serveur de cette fenêtre",
+    terminate_failed: "Impossible de terminer le processus",
+
+    workspace_title: "Ouvrir un espace de travail",
+    workspace_filter: "Tableurs",
+    workspace_unsupported: "Ce serveur ne permet pas de changer d'espace de travail",
+    workspace_refused: "Ouverture refusée",
+
+    updates_title: "Mises à jour",
+    update_available_title: "Mise à jour disponible",
+    update_available: "Une nouvelle version de Tablerreur est disponible.",
+    latest_version: "Dernière version",
+    installed_version: "Version installée",
+    download: "Télécharger",
+    later: "Plus tard",
+    up_to_date: "Vous êtes à jour.",
+    update_check_failed: "Impossible de vérifier les mises à jour :",
+    open_releases: "Ouvrir la page des versions dans le navigateur ?",
+    open_page: "Ouvrir la page",
+    close: "Fermer",
+    copy: "Copier",
+    unknown: "inconnu",
+    curl_unavailable: "curl indisponible :",
+    request_failed: "Requête échouée :",
+    unexpected_response: "Réponse inattendue :",
+    unreadable_version: "Numéro de version illisible",
+
+    at_least_one_worker: "Il faut au moins un worker",
+    concurrency_unsupported: "Ce serveur ne permet pas de régler sa concurrence",
+    concurrency_refused: "Réglage refusé",
+
+    diag_port: "Port",
+    diag_port_range: "Plage de ports",
+    diag_auto_port: "attribué par le système (--port 0)",
+    diag_timeout: "Timeout",
+    diag_seconds: "secondes",
+    diag_system: "Système",
+    diag_server_log: "Journal du serveur",
+    diag_unavailable: "indisponible",
+    diag_exit_code: "Code de sortie",
+    diag_restart_budget: "Budget de redémarrages",
+    diag_per_session: "par session",
+};
+
+const EN: Strings = Strings {
+    lang: "en",
+
+    menu_file: "File",
+    menu_open_workspace: "Open Workspace…",
+    menu_recent: "Recent",
+    menu_no_recent: "No recent files",
+    menu_reload: "Reload",
+    menu_restart_backend: "Restart Server",
+    menu_quit: "Quit",
+    menu_help: "Help",
+    menu_check_updates: "Check for Updates",
+    menu_support_package: "Create Support Package",
+    menu_about: "About Tablerreur",
+    tray_show: "Show",
+    tray_hide: "Hide",
+    splash_starting: "Starting the server…",
+
+    quit_unsaved_title: "Quit without saving?",
+    quit_unsaved: "Some changes have not been saved and will be lost.",
+    cancel: "Cancel",
+
+    error_page_title: "Error — Tablerreur",
+    copy_diag: "Copy diagnostics",
+    copied: "✓ Copied",
+    save_diag: "Save diagnostics",
+    saved_to: "Diagnostics saved to",
+    contact_support: "Contact support with this information.",
+
+    startup_error: "Startup error",
+    startup_timeout: "The Tablerreur server did not start in time.",
+    port_not_announced: "The Tablerreur server did not report which port it listens on.",
+    backend_missing_title: "Server not found",
+    backend_missing: "The Tablerreur server binary is missing. Reinstall the application.",
+    spawn_failed_title: "Startup failed",
+    spawn_failed: "The Tablerreur server could not be launched.",
+    integrity_title: "Security error",
+    integrity_failed: "The embedded server was modified after installation. \
+                       For safety it was not started: reinstall Tablerreur.",
+    repeated_failures_title: "Repeated startup failures",
+    repeated_failures: "The Tablerreur server failed to start on the last launches. \
+                        An invalid configuration is often the cause: you can reset it \
+                        or try again as is.",
+    retry: "Try again",
+    reset_config: "Reset configuration",
+
+    health_timeout: "The server did not respond in time.",
+    backend_stopped_title: "Server stopped",
+    backend_stopped: "The Tablerreur server stopped unexpectedly.",
+    restart_error_title: "Restart error",
+    restart_failed: "The Tablerreur server could not be restarted.",
+    unstable_title: "Unstable server",
+    unstable: "The Tablerreur server had to be restarted too many times this session. \
+               Automatic restarts are suspended.",
+
+    support_package_title: "Support package",
+    support_package_created: "Support package created:",
+    support_package_attach: "Attach this file to your request.",
+    support_package_failed: "Could not create the support package:",
+
+    connection_lost: "Connection to the server lost — reconnecting…",
+    disk_full_title: "Insufficient disk space",
+    disk_full: "The disk is full: Tablerreur stopped writing its logs. \
+                Free some space, then restart the application.",
+    loopback_blocked_title: "Local connection blocked",
+    loopback_blocked: "The local server responds, but the Tablerreur window cannot reach it.\n\n\
+                       A proxy or firewall is probably blocking connections to 127.0.0.1: \
+                       add 127.0.0.1 and localhost to the proxy exceptions (NO_PROXY variable \
+                       or system proxy settings), or allow Tablerreur through the firewall.",
+    server_not_started: "Server not started",
+    server_unreachable: "Server unreachable:",
+
+    free_port_title: "Free the port",
+    free_port_prompt: "Terminate the process holding this port?",
+    free_port_process: "Process",
+    terminate: "Terminate",
+    port_out_of_range: "Port outside the server range:",
+    port_not_held: "No process is listening on port",
+    port_owned: "This port is used by this window's server",
+    terminate_failed: "Could not terminate process",
+
+    workspace_title: "Open a workspace",
+    workspace_filter: "Spreadsheets",
+    workspace_unsupported: "This server cannot switch workspaces",
+    workspace_refused: "Opening refused",
+
+    updates_title: "Updates",
+    update_available_title: "Update available",
+    update_available: "A new version of Tablerreur is available.",
+    latest_version: "Latest version",
+    installed_version: "Installed version",
+    download: "Download",
+    later: "Later",
+    up_to_date: "You are up to date.",
+    update_check_failed: "Could not check for updates:",
+    open_releases: "Open the releases page in the browser?",
+    open_page: "Open page",
+    close: "Close",
+    copy: "Copy",
+    unknown: "unknown",
+    curl_unavailable: "curl unavailable:",
+    request_failed: "Request failed:",
+    unexpected_response: "Unexpected response:",
+    unreadable_version: "Unreadable version number",
+
+    at_least_one_worker: "At least one worker is needed",
+    concurrency_unsupported: "This server cannot change its concurrency",
+    concurrency_refused: "Setting refused",
+
+    diag_port: "Port",
+    diag_port_range: "Port range",
+    diag_auto_port: "assigned by the system (--port 0)",
+    diag_timeout: "Timeout",
+    diag_seconds: "seconds",
+    diag_system: "System",
+    diag_server_log: "Server log",
+    diag_unavailable: "unavailable",
+    diag_exit_code: "Exit code",
+    diag_restart_budget: "Restart budget",
+    diag_per_session: "per session",
+};
+
+/// Language code of a locale value such as `en_US.UTF-8` or `fr-FR`.
+fn language_of(locale: &str) -> &str {
+    locale.split(['_', '-', '.', '@']).next().unwrap_or_default()
+}
+
+/// First non-empty locale variable, in POSIX precedence order.
+fn detect_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Strings for the detected locale.
+pub fn strings() -> &'static Strings {
+    static STRINGS: OnceLock<&'static Strings> = OnceLock::new();
+    STRINGS.get_or_init(|| match detect_locale().as_deref().map(language_of) {
+        Some(lang) if lang.eq_ignore_ascii_case("en") => &EN,
+        _ => &FR,
+    })
+}
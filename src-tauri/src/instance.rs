@@ -0,0 +1,127 @@
+// ---------------------------------------------------------------------------
+// Single instance: a second launch focuses the running window and exits
+// ---------------------------------------------------------------------------
+//
+// The first instance listens on 127.0.0.1:INSTANCE_PORT. The port is fixed
+// rather than derived from the configurable backend range, so that every
+// launch agrees on it; it sits just below the default range, and a range
+// configured over it only sees one port taken by the scan. A later launch
+// that finds the port taken connects and sends `tablerreur focus {user}`; the
+// running instance answers `tablerreur {user}` and brings its main window
+// forward.
+// The second launch then exits before scanning ports or spawning a backend.
+// A second line carries the deep link the launch received (`open {link}`),
+// or is empty; the running instance opens that link itself.
+//
+// Anything else on the port (another program, another user's session) does
+// not answer that way: the launch proceeds, just without the guard. An
+// instance that is restarting itself answers `tablerreur exiting`, and the
+// new process waits for the port to be released.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::deep_link;
+
+const INSTANCE_PORT: u16 = 8399;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a new process waits for a restarting instance to go away.
+const RESTART_WAIT: Duration = Duration::from_secs(10);
+
+/// Set once this instance is about to restart itself.
+static EXITING: AtomicBool = AtomicBool::new(false);
+
+pub enum Launch {
+    /// First instance: keep the listener and pass it to `listen`.
+    First(TcpListener),
+    /// Another instance of this user was told to come forward.
+    AlreadyRunning,
+    /// The port is held by something else: run without the guard.
+    Unguarded,
+}
+
+fn user() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
+}
+
+/// Ask whoever holds the instance port to focus its window and open *link*;
+/// returns its answer.
+fn knock(link: Option<&str>) -> Option<String> {
+    let mut stream = crate::http::connect(INSTANCE_PORT, HANDSHAKE_TIMEOUT).ok()?;
+    let open = link.map(|link| format!("open {link}")).unwrap_or_default();
+    write!(stream, "tablerreur focus {}\n{open}\n", user()).ok()?;
+    let mut answer = String::new();
+    BufReader::new(stream).read_line(&mut answer).ok()?;
+    Some(answer.trim_end().to_string())
+}
+
+/// Decide whether this launch is the first instance. Called before any port
+/// scan or backend spawn; *link* is handed to the running instance, if any.
+pub fn acquire(link: Option<&str>) -> Launch {
+    let deadline = Instant::now() + RESTART_WAIT;
+    loop {
+        if let Ok(listener) = TcpListener::bind(("127.0.0.1", INSTANCE_PORT)) {
+            return Launch::First(listener);
+        }
+        match knock(link) {
+            Some(answer) if answer == format!("tablerreur {}", user()) => {
+                return Launch::AlreadyRunning
+            }
+            Some(answer) if answer == "tablerreur exiting" && Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            _ => return Launch::Unguarded,
+        }
+    }
+}
+
+fn focus_main<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn answer<R: Runtime>(app: &AppHandle<R>, mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    if EXITING.load(Ordering::SeqCst) {
+        return writeln!(stream, "tablerreur exiting");
+    }
+    if request.trim_end() != format!("tablerreur focus {}", user()) {
+        return writeln!(stream, "?");
+    }
+    // Absent from older launches: a timeout there just means no link.
+    let mut open = String::new();
+    let _ = reader.read_line(&mut open);
+    focus_main(app);
+    if let Some(link) = open.trim_end().strip_prefix("open ") {
+        deep_link::open(app, link);
+    }
+    writeln!(stream, "tablerreur {}", user())
+}
+
+/// Serve later launches from a background thread for the app lifetime.
+pub fn listen<R: Runtime>(app: &AppHandle<R>, listener: TcpListener) {
+    let app = app.clone();
+    let _ = std::thread::Builder::new().name("single-instance".into()).spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = answer(&app, stream);
+        }
+    });
+}
+
+/// Called right before the app restarts itself, so the new process is not
+/// mistaken for a second launch.
+pub fn release() {
+    EXITING.store(true, Ordering::SeqCst);
+}
@@ -0,0 +1,92 @@
+// ---------------------------------------------------------------------------
+// Sidecar integrity check
+// ---------------------------------------------------------------------------
+//
+// build.rs embeds the SHA-256 of the bundled backend directory, the onedir
+// launcher together with its `_internal` libraries and data; before every
+// spawn the directory on disk is hashed again and compared. Builds made
+// without the sidecar present (or without TABLERREUR_SIDECAR_SHA256) skip
+// the check.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+use crate::{backend, sha256};
+
+/// Expected hash, embedded at build time.
+const EXPECTED_SHA256: Option<&str> = option_env!("TABLERREUR_SIDECAR_SHA256");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyStatus {
+    /// The sidecar directory matches the embedded hash.
+    Ok,
+    /// The sidecar directory differs from the embedded hash.
+    Mismatch,
+    /// The sidecar directory could not be read.
+    Missing,
+    /// No hash was embedded (or debug build running the Python module).
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyResult {
+    pub status: VerifyStatus,
+    pub path: Option<PathBuf>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+/// Hash the bundled sidecar and compare it with the embedded hash.
+pub fn verify<R: Runtime>(app: &AppHandle<R>) -> VerifyResult {
+    let skipped = VerifyResult {
+        status: VerifyStatus::Skipped,
+        path: None,
+        expected: None,
+        actual: None,
+    };
+    let Some(expected) = EXPECTED_SHA256 else {
+        return skipped;
+    };
+    if cfg!(debug_assertions) {
+        return skipped;
+    }
+    let dir = backend::sidecar_path(app).ok().and_then(|exe| exe.parent().map(PathBuf::from));
+    let Some(path) = dir else {
+        return VerifyResult { status: VerifyStatus::Missing, ..skipped };
+    };
+    let actual = sha256::dir_hex_digest(&path).ok();
+    let status = match &actual {
+        None => VerifyStatus::Missing,
+        Some(actual) if actual.eq_ignore_ascii_case(expected) => VerifyStatus::Ok,
+        Some(_) => VerifyStatus::Mismatch,
+    };
+    VerifyResult {
+        status,
+        path: Some(path),
+        expected: Some(expected.to_string()),
+        actual,
+    }
+}
+
+/// Err with a diagnostic when the sidecar has been tampered with.
+pub fn ensure<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let result = verify(app);
+    if result.status != VerifyStatus::Mismatch {
+        return Ok(());
+    }
+    Err(format!(
+        "Dossier : {}\nAttendu : {}\nObtenu : {}",
+        result.path.as_deref().map(|p| p.display().to_string()).unwrap_or_default(),
+        result.expected.unwrap_or_default(),
+        result.actual.unwrap_or_default()
+    ))
+}
+
+/// Verify the bundled sidecar against the hash embedded at build time.
+#[tauri::command]
+pub async fn verify_sidecar(app: AppHandle) -> VerifyResult {
+    verify(&app)
+}
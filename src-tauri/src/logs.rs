@@ -0,0 +1,388 @@
+// ---------------------------------------------------------------------------
+// Backend output capture
+// ---------------------------------------------------------------------------
+//
+// The sidecar's stdout and stderr are piped and drained by one thread per
+// stream into an in-memory ring buffer, so recent backend output is available
+// even when the backend itself can no longer answer. Every line is also
+// appended, timestamped, to `backend.log` in the app log directory, rotated
+// to a single `backend.log.old` past MAX_FILE_BYTES. The shell's own notes
+// about the backend (restarts, retries) go to the same places, tagged
+// `[tablerreur]`, so the log reads as one timeline.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{backend, disk, http};
+
+/// Captured lines kept in memory.
+const RING_CAPACITY: usize = 2000;
+
+/// Longest line kept from the backend output; the rest of a longer line is
+/// skipped without being buffered, so one huge payload dump cannot exhaust
+/// memory or flood the ring buffer and the IPC channel.
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// Size at which `backend.log` is rotated.
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+const LOG_FILE: &str = "backend.log";
+
+/// Log categories the backend exposes under `/admin/logs/{category}`.
+const CATEGORIES: &[&str] = &["http", "db", "worker"];
+
+/// Most recent backend output lines, oldest first.
+#[derive(Default)]
+pub struct LogBuffer(Mutex<VecDeque<String>>);
+
+/// The open `backend.log` and its current size, shared by both drain
+/// threads. `failed` stops the writes after an I/O error, like the echo.
+#[derive(Default)]
+pub struct LogFile(Mutex<LogSink>);
+
+#[derive(Default)]
+struct LogSink {
+    file: Option<(File, u64)>,
+    failed: bool,
+}
+
+/// Path of the backend log file.
+pub fn log_file_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_log_dir().ok().map(|dir| dir.join(LOG_FILE))
+}
+
+fn open_log(path: &std::path::Path) -> std::io::Result<(File, u64)> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok((file, len))
+}
+
+/// Move `backend.log` to `backend.log.old`, replacing the previous backup.
+fn rotate(path: &std::path::Path) -> std::io::Result<(File, u64)> {
+    let old = path.with_extension("log.old");
+    // rename() does not replace an existing file on Windows.
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(path, &old)?;
+    open_log(path)
+}
+
+fn write_line<R: Runtime>(app: &AppHandle<R>, sink: &mut LogSink, line: &str) -> std::io::Result<()> {
+    let path = log_file_path(app).ok_or(std::io::ErrorKind::NotFound)?;
+    let (file, len) = match &mut sink.file {
+        Some(open) => open,
+        empty => empty.insert(open_log(&path)?),
+    };
+    let stamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    let entry = format!("{stamp} {line}\n");
+    file.write_all(entry.as_bytes())?;
+    *len += entry.len() as u64;
+    if *len > MAX_FILE_BYTES {
+        // Closed first: Windows cannot rename an open file.
+        sink.file = None;
+        sink.file = Some(rotate(&path)?);
+    }
+    Ok(())
+}
+
+fn append_to_file<R: Runtime>(app: &AppHandle<R>, line: &str) {
+    let Some(state) = app.try_state::<LogFile>() else {
+        return;
+    };
+    let Ok(mut sink) = state.0.lock() else {
+        return;
+    };
+    if sink.failed {
+        return;
+    }
+    if let Err(e) = write_line(app, &mut sink, line) {
+        sink.failed = true;
+        sink.file = None;
+        if disk::is_full(&e) {
+            disk::report(app, "logs");
+        }
+    }
+}
+
+fn push_line<R: Runtime>(app: &AppHandle<R>, line: String) {
+    if let Some(buffer) = app.try_state::<LogBuffer>() {
+        if let Ok(mut guard) = buffer.0.lock() {
+            if guard.len() == RING_CAPACITY {
+                guard.pop_front();
+            }
+            guard.push_back(line);
+        }
+    }
+}
+
+/// Record a note of the shell itself next to the backend output: echoed to
+/// stderr in debug builds, kept in the ring buffer and appended to the log.
+pub fn note<R: Runtime>(app: &impl Manager<R>, text: &str) {
+    let app = app.app_handle();
+    let line = format!("[tablerreur] {text}");
+    if cfg!(debug_assertions) {
+        let _ = writeln!(std::io::stderr(), "{line}");
+    }
+    append_to_file(app, &line);
+    push_line(app, line);
+}
+
+/// Last *n* captured lines.
+pub fn tail<R: Runtime>(app: &AppHandle<R>, n: usize) -> Vec<String> {
+    app.try_state::<LogBuffer>()
+        .and_then(|buffer| {
+            buffer.0.lock().ok().map(|guard| {
+                guard.iter().skip(guard.len().saturating_sub(n)).cloned().collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Read one line into *raw*, keeping at most *cap* bytes. Returns the full
+/// length of the line (0 at end of stream); bytes past *cap* are discarded.
+fn read_capped_line(reader: &mut impl BufRead, raw: &mut Vec<u8>, cap: usize) -> std::io::Result<usize> {
+    // One byte of slack tells a line of exactly *cap* bytes plus its newline
+    // apart from a longer one.
+    let mut total = reader.by_ref().take(cap as u64 + 1).read_until(b'\n', raw)?;
+    if raw.last() == Some(&b'\n') || total <= cap {
+        return Ok(total);
+    }
+    raw.truncate(cap);
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(total);
+        }
+        let (used, done) = match chunk.iter().position(|&b| b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (chunk.len(), false),
+        };
+        reader.consume(used);
+        total += used;
+        if done {
+            return Ok(total);
+        }
+    }
+}
+
+/// Port in a `LISTENING ON 127.0.0.1:<port>` line, printed by a backend
+/// started with `--port 0`.
+fn announced_port(line: &str) -> Option<u16> {
+    let addr = line.trim().strip_prefix("LISTENING ON ")?;
+    let (host, port) = addr.rsplit_once(':')?;
+    let port = port.parse::<u16>().ok()?;
+    (host == "127.0.0.1" && port != 0).then_some(port)
+}
+
+/// Drain *stream* line by line. With *watch*, the thread also reports the
+/// end of the stream as a possible death of the child with that PID; with
+/// *announce*, it sends the first port announced on the stream.
+fn drain<R: Runtime>(
+    app: AppHandle<R>,
+    stream: impl Read + Send + 'static,
+    name: &'static str,
+    watch: Option<u32>,
+    mut announce: Option<Sender<u16>>,
+) {
+    let _ = std::thread::Builder::new().name(format!("log-{name}")).spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut raw = Vec::new();
+        // Cleared on the first failed write so a full disk is not retried
+        // for every line; the ring buffer keeps capturing regardless.
+        let mut echo = cfg!(debug_assertions);
+        loop {
+            raw.clear();
+            let len = match read_capped_line(&mut reader, &mut raw, MAX_LINE_BYTES) {
+                Ok(0) | Err(_) => break,
+                Ok(len) => len,
+            };
+            let text = String::from_utf8_lossy(&raw);
+            if let Some(port) = announce.as_ref().and_then(|_| announced_port(&text)) {
+                if let Some(tx) = announce.take() {
+                    let _ = tx.send(port);
+                }
+            }
+            let mut line = format!("[{name}] {}", text.trim_end_matches(['\r', '\n']));
+            if len > raw.len() {
+                line.push_str(&format!("… [ligne tronquée, {len} octets au total]"));
+            }
+            if echo {
+                if let Err(e) = writeln!(std::io::stderr(), "{line}") {
+                    echo = false;
+                    if disk::is_full(&e) {
+                        disk::report(&app, "logs");
+                    }
+                }
+            }
+            append_to_file(&app, &line);
+            push_line(&app, line);
+        }
+        if let Some(pid) = watch {
+            backend::on_output_closed(&app, pid);
+        }
+    });
+}
+
+/// Start draining the piped stdout/stderr of a freshly spawned *child*.
+/// The stdout drain doubles as the exit watcher, since it is the only thread
+/// that sees the child go away, and reads the port announcement into
+/// *announce*.
+pub fn capture<R: Runtime>(app: &AppHandle<R>, child: &mut Child, announce: Option<Sender<u16>>) {
+    let pid = child.id();
+    if let Some(stdout) = child.stdout.take() {
+        drain(app.clone(), stdout, "stdout", Some(pid), announce);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        drain(app.clone(), stderr, "stderr", None, None);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendLog {
+    pub category: String,
+    /// `backend` when served by `/admin/logs`, `local` for the captured output.
+    pub source: &'static str,
+    pub lines: Vec<String>,
+}
+
+/// Lines of a backend answer: either `{"lines": [...]}` or plain text.
+fn parse_log_body(body: &str) -> Vec<String> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| {
+            v.get("lines")?
+                .as_array()
+                .map(|lines| lines.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+        })
+        .unwrap_or_else(|| body.lines().map(str::to_string).collect())
+}
+
+/// Recent backend log lines for *category* (`http`, `db` or `worker`), from
+/// `/admin/logs/{category}` or, if the backend cannot serve it, from the
+/// locally captured stdout/stderr.
+#[tauri::command]
+pub async fn tail_backend_log(
+    app: AppHandle,
+    category: String,
+    lines: usize,
+) -> Result<BackendLog, String> {
+    if !CATEGORIES.contains(&category.as_str()) {
+        return Err(format!(
+            "Catégorie inconnue « {category} » (attendu : {})",
+            CATEGORIES.join(", ")
+        ));
+    }
+    let from_backend = backend::current_port(&app).and_then(|port| {
+        let path = format!("/admin/logs/{category}?lines={lines}");
+        http::get(port, &path, Duration::from_secs(3)).ok().filter(|r| r.status == 200)
+    });
+    Ok(match from_backend {
+        Some(resp) => {
+            let mut all = parse_log_body(&resp.text());
+            let all = all.split_off(all.len().saturating_sub(lines));
+            BackendLog { category, source: "backend", lines: all }
+        }
+        None => BackendLog { category, source: "local", lines: tail(&app, lines) },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Successive `(full length, kept bytes)` reads of *input* with a cap of
+    /// MAX_LINE_BYTES, until the end of the stream.
+    fn read_all(input: &[u8]) -> Vec<(usize, Vec<u8>)> {
+        let mut reader = BufReader::with_capacity(1024, input);
+        let mut reads = Vec::new();
+        loop {
+            let mut raw = Vec::new();
+            let len = read_capped_line(&mut reader, &mut raw, MAX_LINE_BYTES).unwrap();
+            if len == 0 {
+                return reads;
+            }
+            reads.push((len, raw));
+        }
+    }
+
+    #[test]
+    fn reads_short_lines_whole() {
+        let reads = read_all(b"one\ntwo\r\nthree");
+        assert_eq!(
+            reads,
+            [(4, b"one\n".to_vec()), (5, b"two\r\n".to_vec()), (5, b"three".to_vec())]
+        );
+    }
+
+    #[test]
+    fn keeps_a_line_of_exactly_the_cap() {
+        let mut input = vec![b'a'; MAX_LINE_BYTES];
+        input.extend_from_slice(b"\nnext\n");
+        let reads = read_all(&input);
+        assert_eq!(reads.len(), 2);
+        assert_eq!(reads[0].0, MAX_LINE_BYTES + 1);
+        assert_eq!(reads[0].1.len(), MAX_LINE_BYTES + 1);
+        assert_eq!(reads[1], (5, b"next\n".to_vec()));
+    }
+
+    #[test]
+    fn truncates_a_longer_line_and_skips_its_rest() {
+        let mut input = vec![b'a'; MAX_LINE_BYTES];
+        input.extend(vec![b'b'; 3 * MAX_LINE_BYTES]);
+        input.extend_from_slice(b"\nnext\n");
+        let reads = read_all(&input);
+        assert_eq!(reads.len(), 2);
+        assert_eq!(reads[0].0, 4 * MAX_LINE_BYTES + 1);
+        assert_eq!(reads[0].1, vec![b'a'; MAX_LINE_BYTES]);
+        assert_eq!(reads[1], (5, b"next\n".to_vec()));
+    }
+
+    #[test]
+    fn truncates_an_unterminated_line_at_the_end_of_the_stream() {
+        let reads = read_all(&vec![b'a'; MAX_LINE_BYTES + 10]);
+        assert_eq!(reads, [(MAX_LINE_BYTES + 10, vec![b'a'; MAX_LINE_BYTES])]);
+    }
+
+    #[test]
+    fn passes_invalid_utf8_through() {
+        let reads = read_all(b"caf\xe9 \xff\xfe\n");
+        assert_eq!(reads, [(8, b"caf\xe9 \xff\xfe\n".to_vec())]);
+        assert_eq!(String::from_utf8_lossy(&reads[0].1), "caf\u{fffd} \u{fffd}\u{fffd}\n");
+    }
+
+    #[test]
+    fn reads_the_announced_port() {
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:51234"), Some(51234));
+        assert_eq!(announced_port("  LISTENING ON 127.0.0.1:8400\r\n"), Some(8400));
+    }
+
+    #[test]
+    fn ignores_malformed_announcements() {
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:51234 (ready)"), None);
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:51234x"), None);
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:"), None);
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1"), None);
+        assert_eq!(announced_port("INFO LISTENING ON 127.0.0.1:51234"), None);
+        assert_eq!(announced_port("listening on 127.0.0.1:51234"), None);
+    }
+
+    #[test]
+    fn ignores_out_of_range_ports_and_other_hosts() {
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:0"), None);
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:65536"), None);
+        assert_eq!(announced_port("LISTENING ON 127.0.0.1:-1"), None);
+        assert_eq!(announced_port("LISTENING ON 0.0.0.0:51234"), None);
+        assert_eq!(announced_port("LISTENING ON [::1]:51234"), None);
+    }
+}
@@ -1,47 +1,66 @@
 // Prevents additional console window on Windows in release builds.
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::collections::HashMap;
 use std::env::consts::{ARCH, OS};
-use std::net::TcpListener;
-use std::path::PathBuf;
-use std::process::Child;
 use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use tauri::{Manager, Runtime, menu::{MenuBuilder, SubmenuBuilder, MenuItemBuilder}};
+use serde_json::json;
+
+use tauri::{Manager, Runtime, WebviewWindow, menu::{MenuBuilder, SubmenuBuilder, MenuItemBuilder}};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_opener::OpenerExt;
 
-// ---------------------------------------------------------------------------
-// Networking helpers
-// ---------------------------------------------------------------------------
+mod about;
+mod backend;
+mod cache;
+#[cfg(debug_assertions)]
+mod chaos;
+mod concurrency;
+mod crash;
+mod deep_link;
+mod detached;
+mod diagnostics;
+mod disk;
+mod dump;
+mod error_page;
+mod failures;
+mod geometry;
+mod gpu;
+mod health;
+mod heartbeat;
+mod http;
+mod i18n;
+mod instance;
+mod integrity;
+mod logs;
+mod mock_backend;
+mod navigation;
+mod opacity;
+mod ports;
+mod schedule;
+mod settings;
+mod sha256;
+mod support;
+mod timings;
+mod tray;
+mod updates;
+mod user_agent;
+mod workspace;
+mod zip;
 
-/// Return the first free TCP port in [start, end), or None if all are taken.
-fn find_free_port(start: u16, end: u16) -> Option<u16> {
-    for port in start..end {
-        if TcpListener::bind(("127.0.0.1", port)).is_ok() {
-            return Some(port);
-        }
-    }
-    None
-}
-
-/// Poll TCP connectivity on 127.0.0.1:{port} every 200 ms up to *timeout*.
-/// Returns true as soon as the port accepts connections.
-fn wait_for_health(port: u16, timeout: Duration) -> bool {
-    let deadline = Instant::now() + timeout;
-    loop {
-        let addr = format!("127.0.0.1:{port}");
-        if let Ok(addr) = addr.parse() {
-            if std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(500)).is_ok() {
-                return true;
-            }
-        }
-        if Instant::now() >= deadline {
-            return false;
-        }
-        std::thread::sleep(Duration::from_millis(200));
-    }
-}
+use backend::{BackendState, Bind, SidecarState, SpawnError, SpawnedPort};
+use cache::CacheState;
+use detached::AdoptedBackend;
+use diagnostics::FrontendReach;
+use gpu::GpuState;
+use health::ProbeHistory;
+use heartbeat::Heartbeats;
+use logs::{LogBuffer, LogFile};
+use settings::SettingsState;
+use timings::StartupTrace;
+use workspace::RecentMenu;
 
 // ---------------------------------------------------------------------------
 // Splash screen helpers
@@ -51,11 +70,20 @@ fn wait_for_health(port: u16, timeout: Duration) -> bool {
 /// so the webview can load it without any file-system access at runtime.
 const SPLASH_HTML: &str = include_str!("../frontend/index.html");
 
+/// Files the splash references, as (file name, MIME type, content). A
+/// `data:` page has no origin to load them from, so they are inlined.
+const SPLASH_ASSETS: &[(&str, &str, &[u8])] = &[
+    ("splash.css", "text/css", include_bytes!("../frontend/splash.css")),
+    ("logo.png", "image/png", include_bytes!("../frontend/logo.png")),
+];
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
 /// Minimal base64 encoder (RFC 4648) — avoids adding an external crate.
 fn to_base64(data: &[u8]) -> String {
-    const T: &[u8; 64] =
-        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut out = Vec::with_capacity((data.len() + 2) / 3 * 4);
+    const T: &[u8; 64] = BASE64_ALPHABET;
+    let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as u32;
         let b1 = if chunk.len() > 1 { chunk[1] as u32 } else { 0 };
@@ -70,46 +98,233 @@ fn to_base64(data: &[u8]) -> String {
     unsafe { String::from_utf8_unchecked(out) }
 }
 
+/// Why `from_base64` rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecodeError {
+    /// The length is not a multiple of 4.
+    Length(usize),
+    /// Byte at this offset is outside the alphabet (or a misplaced `=`).
+    Byte(usize),
+    /// Padding is malformed or hides non-zero bits.
+    Padding,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Length(len) => write!(f, "base64 : longueur {len} non multiple de 4"),
+            DecodeError::Byte(at) => write!(f, "base64 : caractère invalide à la position {at}"),
+            DecodeError::Padding => write!(f, "base64 : remplissage invalide"),
+        }
+    }
+}
+
+/// Strict decoder for the output of `to_base64`: standard alphabet, padded,
+/// no whitespace. Only canonical encodings are accepted, so whatever decodes
+/// re-encodes to the same text.
+#[allow(dead_code)] // Companion of to_base64; no caller decodes yet.
+fn from_base64(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(DecodeError::Length(bytes.len()));
+    }
+    let value = |at: usize| match bytes[at] {
+        c @ b'A'..=b'Z' => Ok((c - b'A') as u32),
+        c @ b'a'..=b'z' => Ok((c - b'a' + 26) as u32),
+        c @ b'0'..=b'9' => Ok((c - b'0' + 52) as u32),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(DecodeError::Byte(at)),
+    };
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (i, chunk) in bytes.chunks(4).enumerate() {
+        let at = i * 4;
+        let last = at + 4 == bytes.len();
+        // `=` may only end the last chunk, as `xx==` or `xxx=`.
+        let pad = match (chunk[2], chunk[3]) {
+            (b'=', b'=') if last => 2,
+            (b'=', _) => return Err(DecodeError::Padding),
+            (_, b'=') if last => 1,
+            _ => 0,
+        };
+        let mut n = 0;
+        for k in 0..4 - pad {
+            n |= value(at + k)? << (18 - 6 * k);
+        }
+        // A padded chunk must not carry bits past its last full byte.
+        if n & ((1 << (8 * pad)) - 1) != 0 {
+            return Err(DecodeError::Padding);
+        }
+        out.push((n >> 16) as u8);
+        if pad < 2 {
+            out.push((n >> 8) as u8);
+        }
+        if pad < 1 {
+            out.push(n as u8);
+        }
+    }
+    Ok(out)
+}
+
+/// Replace every quoted (`"name"`, `'name'`) or `url(name)` reference to a
+/// file of *assets* in *html* with its `data:` URI.
+fn inline_assets(html: &str, assets: &HashMap<&str, String>) -> String {
+    let mut html = html.to_string();
+    for (name, uri) in assets {
+        for (open, close) in [("\"", "\""), ("'", "'"), ("url(", ")")] {
+            html = html.replace(&format!("{open}{name}{close}"), &format!("{open}{uri}{close}"));
+        }
+    }
+    html
+}
+
+/// The splash page, self-contained.
+fn splash_html() -> String {
+    let assets: HashMap<&str, String> = SPLASH_ASSETS
+        .iter()
+        .map(|(name, mime, data)| (*name, format!("data:{mime};base64,{}", to_base64(data))))
+        .collect();
+    inline_assets(SPLASH_HTML, &assets)
+}
+
+/// Replace the status line of the splash. A no-op once the window has left
+/// it, since no other page has the element.
+fn set_splash_status<R: Runtime>(window: &WebviewWindow<R>, text: &str) {
+    let text = serde_json::to_string(text).unwrap_or_default();
+    let _ = window.eval(format!(
+        "(function(){{var e=document.getElementById('splash-status');if(e)e.textContent={text};}})();"
+    ));
+}
+
+/// Navigate *window* to a self-contained HTML page through a `data:` URI.
+fn navigate_to_html<R: Runtime>(window: &WebviewWindow<R>, html: &str) {
+    let data_url = format!("data:text/html;base64,{}", to_base64(html.as_bytes()));
+    if let Ok(url) = data_url.parse::<tauri::Url>() {
+        let _ = window.navigate(url);
+    }
+}
+
 // ---------------------------------------------------------------------------
-// Managed state: keeps the sidecar child alive for the app lifetime
+// Backend startup
 // ---------------------------------------------------------------------------
 
-struct SidecarState(Mutex<Option<Child>>);
+/// Scan for a free port and start the backend on it (the embedded stub
+/// server when *mock*). With `auto_port` the backend picks its own port and
+/// the returned port is only known once it announces it. May block while a
+/// failed launch is retried: call it off the UI thread.
+fn spawn_backend<R: Runtime>(app: &tauri::AppHandle<R>, mock: bool) -> Result<SpawnedPort, SpawnError> {
+    let limits = backend::limits();
+    let bind = if limits.auto_port && !mock {
+        Bind::Auto
+    } else {
+        // --- Find a free port ---
+        timings::trace(app, "port_scan_start", json!({ "range": [limits.port_min, limits.port_max] }));
+        let reservation = backend::reserve_free_port(limits.port_min, limits.port_max).ok_or_else(|| {
+            SpawnError::Failed(format!(
+                "Aucun port libre trouvé entre {} et {}",
+                limits.port_min, limits.port_max
+            ))
+        })?;
+        let port = backend::reserved_port(&reservation).map_err(SpawnError::Failed)?;
+        timings::trace(app, "port_scan_end", json!({ "port": port }));
+        Bind::Reserved(reservation)
+    };
 
-/// Termine le processus sidecar Python (menu Quitter, croix de fenêtre, Cmd/Ctrl+Q).
-fn kill_sidecar<R: Runtime>(app: &impl Manager<R>) {
-    if let Some(state) = app.try_state::<SidecarState>() {
-        if let Ok(mut guard) = state.0.lock() {
-            if let Some(mut child) = guard.take() {
-                let _ = child.kill();
-                let _ = child.wait(); // éviter processus zombie
-            }
+    // --- Backend HTTP : debug = python -m depuis le dépôt ; release = sidecar PyInstaller ---
+    // TABLERREUR_MOCK_BACKEND replaces it with an embedded stub server.
+    timings::trace(app, "spawn", json!({ "mock": mock }));
+    match bind {
+        Bind::Reserved(reservation) if mock => {
+            let port = backend::reserved_port(&reservation).map_err(SpawnError::Failed)?;
+            mock_backend::start(reservation);
+            Ok(SpawnedPort::Known(port))
+        }
+        bind => {
+            let (child, port) = backend::spawn_sidecar(app, bind)?;
+            // Keep the child process alive in managed state
+            backend::store_child(app, child);
+            Ok(port)
         }
     }
 }
 
+/// Replace the splash with an error page for a backend that could not be
+/// launched at all.
+fn show_spawn_error<R: Runtime>(app: &tauri::AppHandle<R>, error: SpawnError) {
+    let text = i18n::strings();
+    let (title, message, diag) = match error {
+        SpawnError::Missing(diag) => (text.backend_missing_title, text.backend_missing, diag),
+        SpawnError::Failed(diag) => (text.spawn_failed_title, text.spawn_failed, diag),
+    };
+    timings::trace(app, "spawn_failed", json!({ "error": diag }));
+    let _ = timings::write_trace(app);
+    failures::record(app);
+    if let Some(main_win) = app.get_webview_window("main") {
+        let diag = format!("{diag}\n{} : {OS} {ARCH}", text.diag_system);
+        // Navigated rather than evaluated: the splash may still be loading and
+        // would overwrite a page written into it.
+        navigate_to_html(&main_win, &error_page::render(title, message, &diag));
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Entry point
 // ---------------------------------------------------------------------------
 
 fn main() {
+    let launched = Instant::now();
+    // --- A second launch only brings the running window forward ---
+    let link = deep_link::from_args();
+    let instance = match instance::acquire(link.as_deref()) {
+        instance::Launch::First(listener) => Some(listener),
+        instance::Launch::AlreadyRunning => return,
+        instance::Launch::Unguarded => None,
+    };
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_dialog::init())
+        .setup(move |app| {
             // --- Build native menu ---
-            let quit_item = MenuItemBuilder::new("Quitter")
+            let text = i18n::strings();
+            let quit_item = MenuItemBuilder::new(text.menu_quit)
                 .id("quit")
                 .accelerator("CmdOrCtrl+Q")
                 .build(app)?;
-            let file_menu = SubmenuBuilder::new(app, "Fichier")
+            let open_item = MenuItemBuilder::new(text.menu_open_workspace)
+                .id("open-workspace")
+                .build(app)?;
+            let recent_menu = SubmenuBuilder::new(app, text.menu_recent).build()?;
+            let reload_item = MenuItemBuilder::new(text.menu_reload)
+                .id("reload")
+                .accelerator("CmdOrCtrl+R")
+                .build(app)?;
+            let restart_item = MenuItemBuilder::new(text.menu_restart_backend)
+                .id("restart-backend")
+                .build(app)?;
+            let file_menu = SubmenuBuilder::new(app, text.menu_file)
+                .item(&open_item)
+                .item(&recent_menu)
+                .separator()
+                .item(&reload_item)
+                .item(&restart_item)
+                .separator()
                 .item(&quit_item)
                 .build()?;
 
-            let updates_item = MenuItemBuilder::new("Vérifier les mises à jour")
+            let updates_item = MenuItemBuilder::new(text.menu_check_updates)
                 .id("check-updates")
                 .build(app)?;
-            let help_menu = SubmenuBuilder::new(app, "Aide")
+            let support_item = MenuItemBuilder::new(text.menu_support_package)
+                .id("support-package")
+                .build(app)?;
+            let about_item = MenuItemBuilder::new(text.menu_about)
+                .id("about")
+                .build(app)?;
+            let help_menu = SubmenuBuilder::new(app, text.menu_help)
                 .item(&updates_item)
+                .item(&support_item)
+                .separator()
+                .item(&about_item)
                 .build()?;
 
             let menu = MenuBuilder::new(app)
@@ -118,191 +333,399 @@ fn main() {
                 .build()?;
             app.set_menu(menu)?;
 
+            crash::install_hook(app.handle());
+            deep_link::register();
+            app.manage(deep_link::PendingLink::default());
+            if let Some(link) = &link {
+                // Queued: the backend is not up yet.
+                deep_link::open(app.handle(), link);
+            }
+            if let Some(listener) = instance {
+                instance::listen(app.handle(), listener);
+            }
+
+            // --- Load persisted settings, then create the main window ---
+            // The window is not created from the config directly: the GPU
+            // hint has to be in place before its webview starts.
+            let user_settings = settings::load(app.handle());
+            let handle = app.handle().clone();
+            gpu::main_window_builder(app.handle(), user_settings.gpu_preference)?
+                .on_navigation(move |url| match error_page::action_of(url) {
+                    Some(action) if action == error_page::SAVE_ACTION => {
+                        let handle = handle.clone();
+                        let contents = error_page::save_contents(url).unwrap_or_default();
+                        std::thread::spawn(move || error_page::save_from_page(&handle, &contents));
+                        false
+                    }
+                    Some(action) => {
+                        let handle = handle.clone();
+                        std::thread::spawn(move || failures::run_action(&handle, &action));
+                        false
+                    }
+                    None if navigation::allowed(url) => true,
+                    None => {
+                        // Leaving the app: hand the link to the system browser.
+                        if navigation::external(url) {
+                            let _ = handle.opener().open_url(url.as_str(), None::<&str>);
+                        }
+                        false
+                    }
+                })
+                .build()?;
+            app.manage(GpuState {
+                applied: user_settings.gpu_preference,
+                renderer: Mutex::new(None),
+            });
+
+            // --- Restore the window geometry and opacity ---
+            if let Some(main_win) = app.get_webview_window("main") {
+                geometry::restore(&main_win);
+                if user_settings.opacity < opacity::MAX_OPACITY {
+                    if let Some(level) = opacity::clamp_opacity(user_settings.opacity) {
+                        let _ = opacity::apply_opacity(&main_win, level);
+                    }
+                }
+            }
+            let restart_budget = user_settings.restart_budget;
+            app.manage(SettingsState(Mutex::new(user_settings)));
+            app.manage(RecentMenu(recent_menu));
+            workspace::refresh_menu(app.handle());
+            user_agent::restore(app.handle());
+
+            app.manage(SidecarState(Mutex::new(None)));
+            app.manage(BackendState::new(restart_budget));
+            app.manage(ProbeHistory::default());
+            app.manage(Heartbeats::default());
+            app.manage(LogBuffer::default());
+            app.manage(LogFile::default());
+            backend::log_limit_notes(app);
+            app.manage(FrontendReach::default());
+            app.manage(AdoptedBackend::default());
+            app.manage(timings::detect(app.handle(), launched));
+            app.manage(StartupTrace::default());
+            app.manage(CacheState::default());
+            cache::start_monitor(app.handle().clone());
+
+            if let Err(e) = tray::build(app.handle()) {
+                // Without a tray, closing the window quits as before.
+                logs::note(app, &format!("icône de zone de notification indisponible : {e}"));
+            }
+
+            let mock = mock_backend::enabled();
+
+            // --- Stop retrying a backend that failed on every recent launch ---
+            if let Some(diag) = failures::persistent(app) {
+                if let Some(main_win) = app.get_webview_window("main") {
+                    let html = error_page::render_with_actions(
+                        text.repeated_failures_title,
+                        text.repeated_failures,
+                        &diag,
+                        &failures::actions(),
+                    );
+                    navigate_to_html(&main_win, &html);
+                }
+                return Ok(());
+            }
+
             // --- Show splash screen immediately via a data: URI ---
             // Using include_str! + base64 avoids any file-system lookup at runtime,
             // which sidesteps the frontendDist path issues in Tauri dev mode.
             if let Some(splash_win) = app.get_webview_window("main") {
-                let data_url = format!(
-                    "data:text/html;base64,{}",
-                    to_base64(SPLASH_HTML.as_bytes())
-                );
-                if let Ok(url) = data_url.parse::<tauri::Url>() {
-                    let _ = splash_win.navigate(url);
-                }
+                navigate_to_html(&splash_win, &splash_html());
             }
 
-            // --- Find a free port ---
-            let port = find_free_port(8400, 8500)
-                .expect("Aucun port libre trouvé entre 8400 et 8500");
-
-            // --- Backend HTTP : debug = python -m depuis le dépôt ; release = sidecar PyInstaller ---
-            let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .parent()
-                .expect("CARGO_MANIFEST_DIR doit pointer vers src-tauri")
-                .to_path_buf();
-
-            let child = if cfg!(debug_assertions) {
-                let py = std::env::var("TABLERREUR_PYTHON").unwrap_or_else(|_| {
-                    if cfg!(target_os = "windows") {
-                        "python".to_string()
-                    } else {
-                        "python3".to_string()
-                    }
-                });
-                std::process::Command::new(&py)
-                    .current_dir(&repo_root)
-                    .args(["-m", "spreadsheet_qa.web", "--port", &port.to_string()])
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::inherit())
-                    .spawn()
-                    .map_err(|e| {
-                        format!(
-                            "Impossible de lancer le backend Python ({py}) depuis {} : {e}\n\
-                             Définissez TABLERREUR_PYTHON ou installez : pip install -e \".[web]\"",
-                            repo_root.display()
-                        )
-                    })?
-            } else {
-                let exe_name = if cfg!(target_os = "windows") {
-                    "tablerreur-backend.exe"
-                } else {
-                    "tablerreur-backend"
-                };
-                let arch = if cfg!(target_arch = "aarch64") { "aarch64" } else { "x86_64" };
-                let os_part = if cfg!(target_os = "macos") { "apple-darwin" }
-                    else if cfg!(target_os = "linux") { "unknown-linux-gnu" }
-                    else { "pc-windows-msvc" };
-                let triple = format!("{arch}-{os_part}");
-                let exe_path = app
-                    .path()
-                    .resource_dir()
-                    .map_err(|e| format!("Impossible de résoudre resource_dir : {e}"))?
-                    .join("binaries")
-                    .join(format!("tablerreur-backend-{triple}"))
-                    .join(exe_name);
-                if !exe_path.exists() {
-                    return Err(format!(
-                        "Sidecar introuvable : {}\n\
-                         Exécutez d'abord : python scripts/build_sidecar.py",
-                        exe_path.display()
-                    )
-                    .into());
-                }
-                std::process::Command::new(&exe_path)
-                    .args(["--port", &port.to_string()])
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .spawn()
-                    .map_err(|e| format!("Impossible de lancer le sidecar : {e}"))?
-            };
-
-            // Keep the child process alive in managed state
-            app.manage(SidecarState(Mutex::new(Some(child))));
+            // --- Reconnect to a backend detached by the previous launch ---
+            let adopted = if mock { None } else { detached::adopt(app.handle()) };
+            if let Some(port) = adopted {
+                timings::trace(app, "adopt", json!({ "port": port }));
+            }
 
-            // --- Background thread: poll health then navigate ---
+            // --- Background thread: start the backend, poll health then navigate ---
+            // Spawning here keeps the splash responsive while the sidecar is
+            // hashed, while a failed launch is retried, and while the port lookup
+            // for an orphaned backend runs.
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
-                let ready = wait_for_health(port, Duration::from_secs(90));
-
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    if ready {
-                        let url_str = format!("http://127.0.0.1:{port}");
-                        if let Ok(url) = url_str.parse::<tauri::Url>() {
-                            let _ = window.navigate(url);
+                if !mock {
+                    // A backend left running by a crashed launch, stopped before
+                    // the port scan so its port is free again.
+                    backend::kill_orphan(&app_handle);
+                }
+                // --- Refuse to start a sidecar that no longer matches its build hash ---
+                let integrity = if mock { Ok(()) } else { integrity::ensure(&app_handle) };
+                if let Err(diag) = integrity {
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        error_page::show(&window, text.integrity_title, text.integrity_failed, &diag);
+                    }
+                    timings::trace(&app_handle, "integrity_failed", json!({ "diag": diag }));
+                    let _ = timings::write_trace(&app_handle);
+                    return;
+                }
+                let spawned = match adopted {
+                    Some(port) => SpawnedPort::Known(port),
+                    None => match spawn_backend(&app_handle, mock) {
+                        Ok(spawned) => spawned,
+                        Err(e) => {
+                            // The splash stays up and is replaced by the error page.
+                            show_spawn_error(&app_handle, e);
+                            return;
                         }
-                    } else {
-                        // Inject a full error page into the splash webview.
-                        // serde_json::to_string encodes the HTML as a JSON string
-                        // literal (handles quotes, backslashes, newlines) so it
-                        // can be passed safely to document.write().
-                        let diag = format!(
-                            "Port : {port}\nTimeout : 90 secondes\nSystème : {OS} {ARCH}"
-                        );
-                        let html = format!(
-                            r#"<!DOCTYPE html>
-<html lang="fr">
-<head>
-  <meta charset="UTF-8">
-  <meta name="viewport" content="width=device-width, initial-scale=1.0">
-  <title>Erreur — Tablerreur</title>
-  <style>
-    *,*::before,*::after{{box-sizing:border-box;margin:0;padding:0}}
-    html,body{{height:100%;background:#fff8f8;font-family:-apple-system,BlinkMacSystemFont,"Segoe UI",system-ui,sans-serif;-webkit-font-smoothing:antialiased}}
-    body{{display:flex;flex-direction:column;justify-content:center;align-items:center;min-height:100vh;padding:2rem}}
-    .card{{max-width:560px;width:100%}}
-    h1{{font-size:1.5rem;font-weight:700;color:#dc2626;margin-bottom:1rem}}
-    p{{color:#475569;line-height:1.6;margin-bottom:1rem}}
-    pre{{background:#f1f5f9;border:1px solid #e2e8f0;border-radius:6px;padding:1rem;font-size:.85rem;white-space:pre-wrap;user-select:all;color:#334155;margin-bottom:1rem}}
-    button{{background:#2563eb;color:#fff;border:none;border-radius:6px;padding:.5rem 1rem;font-size:.875rem;cursor:pointer;font-family:inherit}}
-    button:hover{{background:#1d4ed8}}
-    .note{{margin-top:1rem;font-size:.8rem;color:#94a3b8}}
-  </style>
-</head>
-<body>
-  <div class="card">
-    <h1>Erreur de démarrage</h1>
-    <p>Le serveur Tablerreur n'a pas pu démarrer dans les délais.</p>
-    <pre id="diag">{diag}</pre>
-    <button id="copy-btn" onclick="copyDiag()">Copier le diagnostic</button>
-    <p class="note">Contactez le support avec ces informations.</p>
-  </div>
-  <script>
-    function copyDiag() {{
-      var t = document.getElementById('diag').textContent;
-      var btn = document.getElementById('copy-btn');
-      if (navigator.clipboard) {{
-        navigator.clipboard.writeText(t).catch(function() {{ fallbackCopy(t); }});
-      }} else {{
-        fallbackCopy(t);
-      }}
-      btn.textContent = '✓ Copié';
-    }}
-    function fallbackCopy(text) {{
-      var ta = document.createElement('textarea');
-      ta.value = text;
-      document.body.appendChild(ta);
-      ta.select();
-      document.execCommand('copy');
-      document.body.removeChild(ta);
-    }}
-  </script>
-</body>
-</html>"#
-                        );
-                        // Encode HTML as a JSON string for safe injection into JS
-                        let json_html = serde_json::to_string(&html)
-                            .unwrap_or_else(|_| "\"Erreur de démarrage\"".to_string());
-                        let js = format!(
-                            "document.open();document.write({json_html});document.close();"
-                        );
-                        let _ = window.eval(&js);
+                    },
+                };
+                let waiting = Instant::now();
+                let splash = app_handle.get_webview_window("main");
+                let port = match spawned.wait() {
+                    Ok(port) => port,
+                    Err(diag) => {
+                        timings::trace(&app_handle, "announce_timeout", json!({ "error": diag }));
+                        let _ = timings::write_trace(&app_handle);
+                        failures::record(&app_handle);
+                        backend::kill_sidecar(&app_handle);
+                        if let Some(window) = &splash {
+                            error_page::show(
+                                window,
+                                text.startup_error,
+                                text.port_not_announced,
+                                &format!(
+                                    "{diag}\n{}\n{} : {OS} {ARCH}",
+                                    backend::limits_diag(),
+                                    text.diag_system
+                                ),
+                            );
+                        }
+                        return;
+                    }
+                };
+                let timeout = backend::limits().health_timeout();
+                let ready = health::wait_for_health_observed(&app_handle, port, timeout, |ok, latency| {
+                    let detail = json!({ "ok": ok, "latency_ms": latency.as_millis() as u64 });
+                    timings::trace(&app_handle, "health_probe", detail);
+                    if let (false, Some(window)) = (ok, &splash) {
+                        let elapsed = waiting.elapsed().as_secs();
+                        set_splash_status(window, &format!("{} ({elapsed}s)", text.splash_starting));
                     }
+                });
+
+                if ready {
+                    backend::mark_ready(&app_handle, port);
+                    timings::trace(&app_handle, "navigate", json!({ "port": port }));
+                    let _ = timings::write_trace(&app_handle);
+                    timings::record_ready(&app_handle);
+                    failures::clear(&app_handle);
+                    health::start_monitor(app_handle.clone());
+                    concurrency::start_monitor(app_handle.clone());
+                    schedule::start_timer(app_handle);
+                } else if let Some(window) = app_handle.get_webview_window("main") {
+                    timings::trace(&app_handle, "health_timeout", json!({ "port": port }));
+                    let _ = timings::write_trace(&app_handle);
+                    failures::record(&app_handle);
+                    let log = logs::log_file_path(&app_handle)
+                        .map_or_else(|| text.diag_unavailable.to_string(), |p| p.display().to_string());
+                    let diag = format!(
+                        "{} : {port}\n{}\n{} : {OS} {ARCH}\n{} : {log}",
+                        text.diag_port,
+                        backend::limits_diag(),
+                        text.diag_system,
+                        text.diag_server_log
+                    );
+                    error_page::show(&window, text.startup_error, text.startup_timeout, &diag);
                 }
             });
 
             Ok(())
         })
+        .invoke_handler(navigation::guard_commands(tauri::generate_handler![
+            opacity::set_opacity,
+            ports::ports_in_use,
+            ports::kill_port_holder,
+            schedule::set_scheduled_restart,
+            schedule::next_scheduled_restart,
+            integrity::verify_sidecar,
+            support::create_support_package,
+            dump::capture_backend_dump,
+            error_page::save_diagnostic,
+            backend::hot_swap_backend,
+            diagnostics::loopback_status,
+            diagnostics::frontend_can_reach,
+            diagnostics::network_diagnostics,
+            backend::restart_budget,
+            backend::reset_restart_budget,
+            #[cfg(debug_assertions)]
+            heartbeat::runtime_state,
+            #[cfg(debug_assertions)]
+            heartbeat::pause_monitors,
+            #[cfg(debug_assertions)]
+            chaos::simulate_backend_failure,
+            logs::tail_backend_log,
+            user_agent::set_user_agent,
+            timings::start_timings,
+            timings::export_startup_trace,
+            backend::backend_port,
+            backend::rebind_backend,
+            gpu::gpu_status,
+            gpu::report_webview_gpu,
+            gpu::set_gpu_preference,
+            concurrency::set_backend_concurrency,
+            concurrency::backend_concurrency,
+            workspace::open_workspace,
+            cache::webview_cache_status,
+            cache::set_webview_cache_quota,
+        ]))
         .on_menu_event(|app, event| {
             match event.id().as_ref() {
-                "quit" => {
-                    kill_sidecar(app);
-                    app.exit(0);
+                "quit" => tray::confirm_quit(app),
+                "reload" => backend::reload(app),
+                "restart-backend" => {
+                    // Shuts the current child down, or kills it, before respawning it.
+                    let app = app.clone();
+                    std::thread::spawn(move || backend::restart_or_show_error(&app));
+                }
+                "about" => {
+                    let app = app.clone();
+                    std::thread::spawn(move || about::show(&app));
                 }
                 "check-updates" => {
-                    // Open releases page in the default browser
-                    let _ = app.opener().open_url(
-                        "https://github.com/Hsbtqemy/Tablerreur/releases",
-                        None::<&str>,
-                    );
+                    let app = app.clone();
+                    std::thread::spawn(move || updates::check(&app));
+                }
+                "support-package" => {
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        let strings = i18n::strings();
+                        let (kind, text) = match support::create_package(&app) {
+                            Ok(path) => {
+                                let _ = app.opener().reveal_item_in_dir(&path);
+                                (
+                                    MessageDialogKind::Info,
+                                    format!(
+                                        "{}\n{}\n\n{}",
+                                        strings.support_package_created,
+                                        path.display(),
+                                        strings.support_package_attach
+                                    ),
+                                )
+                            }
+                            Err(e) => (
+                                MessageDialogKind::Error,
+                                format!("{}\n{e}", strings.support_package_failed),
+                            ),
+                        };
+                        app.dialog()
+                            .message(text)
+                            .title(strings.support_package_title)
+                            .kind(kind)
+                            .show(|_| {});
+                    });
+                }
+                id => {
+                    workspace::handle_menu(app, id);
                 }
-                _ => {}
             }
         })
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                kill_sidecar(window.app_handle());
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() != "main" {
+                    return;
+                }
+                api.prevent_close();
+                // Hidden to the tray, or quit once unsaved work, if any, is
+                // confirmed: `tray::quit` saves the geometry and stops the backend.
+                if !tray::hide_on_close(window.app_handle()) {
+                    tray::confirm_quit(window.app_handle());
+                }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("Erreur lors du lancement de Tablerreur");
+        .build(tauri::generate_context!())
+        .expect("Erreur lors du lancement de Tablerreur")
+        .run(|_app, _event| {
+            // macOS: a click on the Dock icon brings back the window hidden
+            // on close, the app having stayed alive in the background.
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Reopen { has_visible_windows: false, .. } = _event {
+                tray::show_main(_app);
+            }
+            // macOS delivers deep links to the running app, launch included.
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &_event {
+                for url in urls {
+                    deep_link::open(_app, url.as_str());
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"<link rel="stylesheet" href="splash.css">
+<img src='logo.png' alt="logo.png icon">
+<div style="background:url(logo.png)"></div>
+<img src="other.png">"#;
+
+    #[test]
+    fn encodes_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(to_base64(plain.as_bytes()), encoded);
+            assert_eq!(from_base64(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn round_trips_every_length() {
+        let data: Vec<u8> = (0..=255).collect();
+        for len in 0..data.len() {
+            let encoded = to_base64(&data[..len]);
+            assert_eq!(encoded.len(), len.div_ceil(3) * 4);
+            assert_eq!(from_base64(&encoded).unwrap(), &data[..len], "length {len}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(from_base64("Zg="), Err(DecodeError::Length(3)));
+        assert_eq!(from_base64("Zm9v!A=="), Err(DecodeError::Byte(4)));
+        assert_eq!(from_base64("Zm 9"), Err(DecodeError::Byte(2)));
+        assert_eq!(from_base64("Zg==Zm9v"), Err(DecodeError::Padding));
+        assert_eq!(from_base64("Z=g="), Err(DecodeError::Byte(1)));
+        assert_eq!(from_base64("Zg=v"), Err(DecodeError::Padding));
+        assert_eq!(from_base64("===="), Err(DecodeError::Byte(0)));
+        // Non-zero bits hidden under the padding.
+        assert_eq!(from_base64("Zh=="), Err(DecodeError::Padding));
+        assert_eq!(from_base64("Zm9="), Err(DecodeError::Padding));
+    }
+
+    #[test]
+    fn inlines_referenced_assets() {
+        let assets = HashMap::from([
+            ("splash.css", "data:text/css;base64,Ym9keXt9".to_string()),
+            ("logo.png", "data:image/png;base64,iVBO".to_string()),
+        ]);
+        assert_eq!(
+            inline_assets(FIXTURE, &assets),
+            r#"<link rel="stylesheet" href="data:text/css;base64,Ym9keXt9">
+<img src='data:image/png;base64,iVBO' alt="logo.png icon">
+<div style="background:url(data:image/png;base64,iVBO)"></div>
+<img src="other.png">"#
+        );
+    }
+
+    #[test]
+    fn splash_has_no_file_references_left() {
+        let html = splash_html();
+        for (name, _, _) in SPLASH_ASSETS {
+            assert!(!html.contains(&format!("\"{name}\"")), "{name} still referenced");
+        }
+        assert!(html.contains("href=\"data:text/css;base64,"));
+        assert!(html.contains("src=\"data:image/png;base64,"));
+    }
 }
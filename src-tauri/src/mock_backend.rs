@@ -0,0 +1,78 @@
+// ---------------------------------------------------------------------------
+// Embedded mock backend (TABLERREUR_MOCK_BACKEND=1)
+// ---------------------------------------------------------------------------
+//
+// Lets frontend contributors run the desktop shell without the Python
+// toolchain: instead of spawning the sidecar, a tiny HTTP server answers the
+// endpoints the shell itself needs and serves a static placeholder page.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+const PLACEHOLDER_HTML: &str = r#"<!DOCTYPE html>
+<html lang="fr">
+<head>
+  <meta charset="UTF-8">
+  <title>Tablerreur — serveur simulé</title>
+  <style>
+    html,body{height:100%;margin:0;font-family:-apple-system,BlinkMacSystemFont,"Segoe UI",system-ui,sans-serif;background:#f8fafc;color:#334155}
+    body{display:flex;flex-direction:column;justify-content:center;align-items:center}
+    h1{color:#2563eb;margin-bottom:.5rem}
+    code{background:#e2e8f0;border-radius:4px;padding:.1rem .3rem}
+  </style>
+</head>
+<body>
+  <h1>Tablerreur</h1>
+  <p>Serveur simulé : <code>TABLERREUR_MOCK_BACKEND</code> est actif, aucun backend Python n'a été lancé.</p>
+  <script>
+    window.__TAURI__ && window.__TAURI__.core.invoke('frontend_can_reach').catch(function () {});
+  </script>
+</body>
+</html>
+"#;
+
+/// True when TABLERREUR_MOCK_BACKEND is set to anything but empty or `0`.
+pub fn enabled() -> bool {
+    std::env::var("TABLERREUR_MOCK_BACKEND").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+fn respond(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(stream.try_clone()?);
+    reader.read_line(&mut request_line)?;
+    // Consume the headers so the client sees a clean close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or("/");
+    let version = env!("CARGO_PKG_VERSION");
+    let (status, content_type, body) = match path {
+        "/health" => (
+            "200 OK",
+            "application/json",
+            format!(r#"{{"status":"ok","version":"{version}-mock"}}"#),
+        ),
+        "/version" => ("200 OK", "application/json", format!(r#"{{"version":"{version}-mock"}}"#)),
+        "/" => ("200 OK", "text/html; charset=utf-8", PLACEHOLDER_HTML.to_string()),
+        _ => ("404 Not Found", "application/json", r#"{"detail":"Not Found"}"#.to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Serve the mock backend on the already bound *listener* from a background
+/// thread.
+pub fn start(listener: TcpListener) {
+    let _ = std::thread::Builder::new().name("mock-backend".into()).spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = respond(stream);
+        }
+    });
+}
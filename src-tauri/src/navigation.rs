@@ -0,0 +1,124 @@
+// ---------------------------------------------------------------------------
+// Navigation guard for the main window
+// ---------------------------------------------------------------------------
+//
+// The window only ever shows the splash and error pages (`data:`), the
+// backend on 127.0.0.1 and the project's releases page. Any other link the
+// backend renders opens in the system browser instead of taking the whole
+// app window away from the tool.
+//
+// The IPC capability admits any 127.0.0.1 port, since the backend range is
+// configurable and `--port 0` lets the system pick it. The app commands are
+// narrowed here instead: from 127.0.0.1 only the live backend's port may
+// call them.
+
+use tauri::ipc::Invoke;
+use tauri::{Runtime, Url};
+
+use crate::backend;
+
+const RELEASES_HOST: &str = "github.com";
+const RELEASES_PATH: &str = "/Hsbtqemy/Tablerreur/releases";
+
+/// Whether the main window may navigate to *url* itself.
+pub fn allowed(url: &Url) -> bool {
+    match url.scheme() {
+        "data" => true,
+        "about" => url.path() == "blank",
+        "http" => url.host_str() == Some("127.0.0.1"),
+        "https" => {
+            let path = url.path();
+            url.host_str() == Some(RELEASES_HOST)
+                && (path == RELEASES_PATH || path.starts_with(&format!("{RELEASES_PATH}/")))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a page at *url* may call the app commands while the backend
+/// listens on *backend_port*. Pages off 127.0.0.1 only get this far when a
+/// capability already covers them (the app's own assets).
+pub fn ipc_allowed(url: &Url, backend_port: Option<u16>) -> bool {
+    if url.host_str() != Some("127.0.0.1") {
+        return true;
+    }
+    url.scheme() == "http" && url.port().is_some() && url.port() == backend_port
+}
+
+/// Wrap the generated command handler so that calls from a page `ipc_allowed`
+/// refuses are rejected before reaching any command.
+pub fn guard_commands<R: Runtime>(
+    commands: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let webview = invoke.message.webview_ref();
+        let port = backend::current_port(webview);
+        if !webview.url().is_ok_and(|url| ipc_allowed(&url, port)) {
+            invoke.resolver.reject("Commande refusée : la page n'est pas servie par le serveur Tablerreur");
+            return true;
+        }
+        commands(invoke)
+    }
+}
+
+/// Whether *url* can be handed to the system browser.
+pub fn external(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https" | "mailto")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(url: &str) -> bool {
+        allowed(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn allows_app_pages() {
+        assert!(check("data:text/html;base64,PGh0bWw+"));
+        assert!(check("about:blank"));
+        assert!(check("http://127.0.0.1:8400/"));
+        assert!(check("http://127.0.0.1:8499/api/jobs?id=3"));
+    }
+
+    #[test]
+    fn allows_releases_pages() {
+        assert!(check("https://github.com/Hsbtqemy/Tablerreur/releases"));
+        assert!(check("https://github.com/Hsbtqemy/Tablerreur/releases/tag/v0.2.0"));
+    }
+
+    #[test]
+    fn rejects_everything_else() {
+        assert!(!check("https://example.com/"));
+        assert!(!check("http://localhost:8400/"));
+        assert!(!check("http://127.0.0.2:8400/"));
+        assert!(!check("https://127.0.0.1:8400/"));
+        assert!(!check("https://github.com/Hsbtqemy/Tablerreur"));
+        assert!(!check("https://github.com/Hsbtqemy/Tablerreur/releases-fake"));
+        assert!(!check("https://github.com.evil.test/Hsbtqemy/Tablerreur/releases"));
+        assert!(!check("http://github.com/Hsbtqemy/Tablerreur/releases"));
+        assert!(!check("file:///etc/passwd"));
+        assert!(!check("about:config"));
+    }
+
+    #[test]
+    fn commands_only_from_the_live_backend() {
+        let ipc = |url: &str, port| ipc_allowed(&url.parse().unwrap(), port);
+        assert!(ipc("http://127.0.0.1:8400/", Some(8400)));
+        assert!(ipc("http://127.0.0.1:51234/report/7", Some(51234)));
+        assert!(ipc("tauri://localhost/", None));
+        assert!(!ipc("http://127.0.0.1:8401/", Some(8400)));
+        assert!(!ipc("http://127.0.0.1:8400/", None));
+        assert!(!ipc("http://127.0.0.1/", Some(80)));
+        assert!(!ipc("https://127.0.0.1:8400/", Some(8400)));
+    }
+
+    #[test]
+    fn only_web_links_go_to_the_browser() {
+        assert!(external(&"https://example.com/".parse().unwrap()));
+        assert!(external(&"mailto:support@example.com".parse().unwrap()));
+        assert!(!external(&"file:///etc/passwd".parse().unwrap()));
+        assert!(!external(&"javascript:alert(1)".parse().unwrap()));
+    }
+}
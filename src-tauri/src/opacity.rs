@@ -0,0 +1,84 @@
+// ---------------------------------------------------------------------------
+// Window opacity ("reference overlay" mode)
+// ---------------------------------------------------------------------------
+//
+// Platform notes:
+// - Windows: the top-level HWND is switched to a layered window and its alpha
+//   set with SetLayeredWindowAttributes. WebView2 content follows the parent.
+// - macOS: NSWindow.alphaValue, applied on the main thread.
+// - Linux: gtk_widget_set_opacity on the GTK window. This needs a compositing
+//   window manager; without one (or on some Wayland compositors) the call is
+//   accepted but the window stays opaque.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings;
+
+/// Lowest accepted opacity: below this the window becomes hard to find again.
+pub const MIN_OPACITY: f64 = 0.2;
+pub const MAX_OPACITY: f64 = 1.0;
+
+/// Clamp *level* to [MIN_OPACITY, MAX_OPACITY]; non-finite values are rejected.
+pub fn clamp_opacity(level: f64) -> Option<f64> {
+    level.is_finite().then(|| level.clamp(MIN_OPACITY, MAX_OPACITY))
+}
+
+/// Apply *level* to *window* through the platform API, on the main thread.
+pub fn apply_opacity<R: Runtime>(window: &WebviewWindow<R>, level: f64) -> Result<(), String> {
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || platform_apply(&target, level))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_apply<R: Runtime>(window: &WebviewWindow<R>, level: f64) {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+        LWA_ALPHA, WS_EX_LAYERED,
+    };
+    if let Ok(hwnd) = window.hwnd() {
+        let hwnd = hwnd.0 as _;
+        // SAFETY: hwnd is the live top-level window owned by this process.
+        unsafe {
+            let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+            SetLayeredWindowAttributes(hwnd, 0, (level * 255.0).round() as u8, LWA_ALPHA);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_apply<R: Runtime>(window: &WebviewWindow<R>, level: f64) {
+    use objc2::{msg_send, runtime::AnyObject};
+    if let Ok(ns_window) = window.ns_window() {
+        // SAFETY: ns_window is a valid NSWindow pointer and we are on the main thread.
+        unsafe {
+            let ns_window = &*(ns_window as *const AnyObject);
+            let _: () = msg_send![ns_window, setAlphaValue: level];
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn platform_apply<R: Runtime>(window: &WebviewWindow<R>, level: f64) {
+    use gtk::prelude::WidgetExt;
+    if let Ok(gtk_window) = window.gtk_window() {
+        gtk_window.set_opacity(level);
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn platform_apply<R: Runtime>(_window: &WebviewWindow<R>, _level: f64) {}
+
+/// Set the main window opacity, clamped to [0.2, 1.0], and persist it.
+/// Returns the level actually applied.
+#[tauri::command]
+pub fn set_opacity(app: AppHandle, level: f64) -> Result<f64, String> {
+    let level = clamp_opacity(level).ok_or("Opacité invalide")?;
+    if let Some(window) = app.get_webview_window("main") {
+        apply_opacity(&window, level)?;
+    }
+    settings::update(&app, |s| s.opacity = level)?;
+    Ok(level)
+}
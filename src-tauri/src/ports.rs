@@ -0,0 +1,381 @@
+// ---------------------------------------------------------------------------
+// Processes holding ports in the backend range
+// ---------------------------------------------------------------------------
+//
+// A previous instance that crashed can leave its backend listening on a port
+// of the range, and enough of them end in "Aucun port libre". These commands
+// show who holds each port and let the user terminate it.
+
+use std::process::Command;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::backend::{self, SidecarState};
+use crate::{detached, i18n};
+
+/// A process listening on a port of the backend range.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortHolder {
+    pub port: u16,
+    pub pid: u32,
+    pub name: String,
+    /// Bound to 127.0.0.1 or ::1 rather than a wildcard or external address.
+    #[serde(skip)]
+    pub loopback: bool,
+}
+
+/// Run *program* and return its stdout, or None if it could not be run.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Port number at the end of an address such as `127.0.0.1:8400` or `[::1]:8400`.
+fn port_of(addr: &str) -> Option<u16> {
+    addr.rsplit(':').next()?.parse().ok()
+}
+
+/// Whether the host part of *addr* is a loopback address.
+fn is_loopback(addr: &str) -> bool {
+    let host = addr.rsplit_once(':').map_or(addr, |(host, _)| host);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.starts_with("127.") || host == "::1"
+}
+
+/// Whether a process name is the backend: the sidecar binary (names are
+/// truncated to 15 characters by `ss`) or the Python interpreter in dev.
+fn looks_like_backend(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("tablerreur-back") || name.contains("python")
+}
+
+fn in_range(port: u16) -> bool {
+    backend::limits().contains(port)
+}
+
+/// Parse `lsof -F pcn` output: `p<pid>`, `c<command>` then one or more `n<addr>`.
+#[cfg(unix)]
+fn parse_lsof(output: &str) -> Vec<PortHolder> {
+    let mut holders = Vec::new();
+    let (mut pid, mut name) = (0u32, String::new());
+    for line in output.lines() {
+        let (tag, value) = line.split_at(line.len().min(1));
+        match tag {
+            "p" => pid = value.parse().unwrap_or(0),
+            "c" => name = value.to_string(),
+            "n" => {
+                if let Some(port) = port_of(value) {
+                    let loopback = is_loopback(value);
+                    holders.push(PortHolder { port, pid, name: name.clone(), loopback });
+                }
+            }
+            _ => {}
+        }
+    }
+    holders
+}
+
+/// Parse `ss -ltnpH` output, e.g.
+/// `LISTEN 0 2048 127.0.0.1:8400 0.0.0.0:* users:(("python3",pid=1234,fd=6))`.
+#[cfg(target_os = "linux")]
+fn parse_ss(output: &str) -> Vec<PortHolder> {
+    let mut holders = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let Some(addr) = fields.get(3) else {
+            continue;
+        };
+        let Some(port) = port_of(addr) else {
+            continue;
+        };
+        let Some(users) = fields.iter().find(|f| f.starts_with("users:")) else {
+            continue;
+        };
+        let name = users.split('"').nth(1).unwrap_or_default().to_string();
+        let pid = users
+            .split("pid=")
+            .nth(1)
+            .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|pid| pid.parse().ok())
+            .unwrap_or(0);
+        holders.push(PortHolder { port, pid, name, loopback: is_loopback(addr) });
+    }
+    holders
+}
+
+/// Parse `netstat -ano -p TCP` output, e.g.
+/// `  TCP    127.0.0.1:8400    0.0.0.0:0    LISTENING    1234`.
+#[cfg(target_os = "windows")]
+fn parse_netstat(output: &str) -> Vec<PortHolder> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 || fields[3] != "LISTENING" {
+                return None;
+            }
+            Some(PortHolder {
+                port: port_of(fields[1])?,
+                pid: fields[4].parse().ok()?,
+                name: String::new(),
+                loopback: is_loopback(fields[1]),
+            })
+        })
+        .collect()
+}
+
+/// Image name of *pid* from `tasklist /FO CSV /NH`: `"python.exe","1234",…`.
+#[cfg(target_os = "windows")]
+fn process_name(pid: u32) -> String {
+    command_output("tasklist", &["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .and_then(|out| out.split('"').nth(1).map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn list_listeners() -> Vec<PortHolder> {
+    let mut holders = parse_netstat(&command_output("netstat", &["-ano", "-p", "TCP"]).unwrap_or_default());
+    for holder in &mut holders {
+        holder.name = process_name(holder.pid);
+    }
+    holders
+}
+
+#[cfg(unix)]
+fn list_listeners() -> Vec<PortHolder> {
+    #[cfg(target_os = "linux")]
+    {
+        let holders = parse_ss(&command_output("ss", &["-ltnpH"]).unwrap_or_default());
+        if !holders.is_empty() {
+            return holders;
+        }
+    }
+    let limits = backend::limits();
+    let range = format!("-iTCP:{}-{}", limits.port_min, limits.port_max - 1);
+    parse_lsof(&command_output("lsof", &["-nP", &range, "-sTCP:LISTEN", "-F", "pcn"]).unwrap_or_default())
+}
+
+/// Listeners in the backend range, one entry per port, sorted by port.
+fn holders_in_range() -> Vec<PortHolder> {
+    let mut holders: Vec<PortHolder> =
+        list_listeners().into_iter().filter(|h| in_range(h.port)).collect();
+    holders.sort_by_key(|h| h.port);
+    holders.dedup_by_key(|h| h.port);
+    holders
+}
+
+/// Every listener of *pid*, on any port.
+#[cfg(target_os = "windows")]
+fn listeners_of(pid: u32) -> Vec<PortHolder> {
+    let mut holders: Vec<PortHolder> =
+        parse_netstat(&command_output("netstat", &["-ano", "-p", "TCP"]).unwrap_or_default())
+            .into_iter()
+            .filter(|h| h.pid == pid)
+            .collect();
+    if !holders.is_empty() {
+        let name = process_name(pid);
+        for holder in &mut holders {
+            holder.name = name.clone();
+        }
+    }
+    holders
+}
+
+#[cfg(unix)]
+fn listeners_of(pid: u32) -> Vec<PortHolder> {
+    #[cfg(target_os = "linux")]
+    {
+        let holders = parse_ss(&command_output("ss", &["-ltnpH"]).unwrap_or_default());
+        if !holders.is_empty() {
+            return holders.into_iter().filter(|h| h.pid == pid).collect();
+        }
+    }
+    // `+c 0`: untruncated command names, which are 9 characters by default.
+    let pid = pid.to_string();
+    let args = ["-nP", "+c", "0", "-a", "-p", &pid, "-iTCP", "-sTCP:LISTEN", "-F", "pcn"];
+    parse_lsof(&command_output("lsof", &args).unwrap_or_default())
+}
+
+/// Whether *pid* listens on a loopback port, in the range or not, under a
+/// backend process name. This recognises a backend started with `--port 0`.
+pub fn holds_loopback_backend_port(pid: u32) -> bool {
+    listeners_of(pid).iter().any(|h| h.loopback && looks_like_backend(&h.name))
+}
+
+/// PID listening on *port* in the backend range, if any.
+pub fn holder_pid(port: u16) -> Option<u32> {
+    holders_in_range().into_iter().find(|h| h.port == port).map(|h| h.pid)
+}
+
+/// Whether *pid* listens on a port of the backend range.
+pub fn holds_backend_port(pid: u32) -> bool {
+    holders_in_range().iter().any(|h| h.pid == pid)
+}
+
+/// Terminate *pid*: SIGTERM first, SIGKILL if it is still alive a second later.
+#[cfg(unix)]
+pub fn terminate(pid: u32) -> Result<(), String> {
+    let pid = pid.to_string();
+    let status = Command::new("kill").args(["-TERM", &pid]).status().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("{} (kill -TERM {pid})", i18n::strings().terminate_failed));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    let alive = Command::new("kill").args(["-0", &pid]).status().is_ok_and(|s| s.success());
+    if alive {
+        let _ = Command::new("kill").args(["-KILL", &pid]).status();
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn terminate(pid: u32) -> Result<(), String> {
+    command_output("taskkill", &["/PID", &pid.to_string(), "/T", "/F"])
+        .map(|_| ())
+        .ok_or_else(|| format!("{} (taskkill /PID {pid})", i18n::strings().terminate_failed))
+}
+
+/// List the processes listening on ports of the backend range.
+#[tauri::command]
+pub async fn ports_in_use() -> Vec<PortHolder> {
+    holders_in_range()
+}
+
+/// Terminate the process holding *port*, after a native confirmation dialog.
+/// Returns false if the user cancelled. The live backend of this instance,
+/// spawned or adopted, is never terminated this way (use the restart instead).
+#[tauri::command]
+pub async fn kill_port_holder(app: AppHandle, port: u16) -> Result<bool, String> {
+    let text = i18n::strings();
+    if !in_range(port) {
+        let limits = backend::limits();
+        return Err(format!(
+            "{} {port} ({}–{})",
+            text.port_out_of_range, limits.port_min, limits.port_max
+        ));
+    }
+    let holder = holders_in_range()
+        .into_iter()
+        .find(|h| h.port == port)
+        .ok_or_else(|| format!("{} {port}", text.port_not_held))?;
+
+    let own_pid = app
+        .try_state::<SidecarState>()
+        .and_then(|state| state.0.lock().ok().and_then(|c| c.as_ref().map(|c| c.id())));
+    if own_pid == Some(holder.pid) || detached::adopted_pid(&app) == Some(holder.pid) {
+        return Err(text.port_owned.to_string());
+    }
+
+    let confirmed = app
+        .dialog()
+        .message(format!(
+            "{}\n\n{} : {port}\n{} : {} (PID {})",
+            text.free_port_prompt, text.diag_port, text.free_port_process, holder.name, holder.pid
+        ))
+        .title(text.free_port_title)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            text.terminate.to_string(),
+            text.cancel.to_string(),
+        ))
+        .blocking_show();
+    if !confirmed {
+        return Ok(false);
+    }
+    terminate(holder.pid)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(holders: &[PortHolder]) -> Vec<(u16, u32, &str)> {
+        holders.iter().map(|h| (h.port, h.pid, h.name.as_str())).collect()
+    }
+
+    #[test]
+    fn reads_the_port_of_an_address() {
+        assert_eq!(port_of("127.0.0.1:8400"), Some(8400));
+        assert_eq!(port_of("[::1]:8401"), Some(8401));
+        assert_eq!(port_of("*:8402"), Some(8402));
+        assert_eq!(port_of("127.0.0.1:*"), None);
+        assert_eq!(port_of("127.0.0.1"), None);
+    }
+
+    #[test]
+    fn recognises_loopback_addresses() {
+        assert!(is_loopback("127.0.0.1:8400"));
+        assert!(is_loopback("127.0.1.1:41234"));
+        assert!(is_loopback("[::1]:8401"));
+        assert!(!is_loopback("0.0.0.0:8400"));
+        assert!(!is_loopback("*:8402"));
+        assert!(!is_loopback("[::]:8401"));
+        assert!(!is_loopback("192.168.1.10:8400"));
+    }
+
+    #[test]
+    fn recognises_backend_process_names() {
+        assert!(looks_like_backend("tablerreur-back"));
+        assert!(looks_like_backend("tablerreur-backend-x86_64-pc-windows-msvc.exe"));
+        assert!(looks_like_backend("python3"));
+        assert!(looks_like_backend("Python"));
+        assert!(!looks_like_backend("tablerreur"));
+        assert!(!looks_like_backend("node"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parses_lsof() {
+        let output = "p1234\ncpython3\nf6\nn127.0.0.1:8400\nf7\nn[::1]:8401\n\
+                      p99\ncnode\nf21\nn127.0.0.1:3000\n";
+        assert_eq!(
+            summary(&parse_lsof(output)),
+            [(8400, 1234, "python3"), (8401, 1234, "python3"), (3000, 99, "node")]
+        );
+        assert!(parse_lsof("").is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_ss() {
+        let output = "\
+LISTEN 0      2048       127.0.0.1:8400      0.0.0.0:*    users:((\"python3\",pid=1234,fd=6))
+LISTEN 0      4096           [::1]:8401         [::]:*    users:((\"tablerreur-back\",pid=1235,fd=7))
+LISTEN 0      511        127.0.0.1:3000      0.0.0.0:*    users:((\"node\",pid=99,fd=21),(\"node\",pid=100,fd=21))
+LISTEN 0      128          0.0.0.0:22        0.0.0.0:*
+";
+        assert_eq!(
+            summary(&parse_ss(output)),
+            [(8400, 1234, "python3"), (8401, 1235, "tablerreur-back"), (3000, 99, "node")]
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn parses_netstat() {
+        let output = "\r
+Active Connections\r
+\r
+  Proto  Local Address          Foreign Address        State           PID\r
+  TCP    127.0.0.1:8400         0.0.0.0:0              LISTENING       1234\r
+  TCP    [::1]:8401             [::]:0                 LISTENING       1235\r
+  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       980\r
+  TCP    127.0.0.1:8400         127.0.0.1:50123        ESTABLISHED     1234\r
+";
+        assert_eq!(
+            summary(&parse_netstat(output)),
+            [(8400, 1234, ""), (8401, 1235, ""), (135, 980, "")]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+// ---------------------------------------------------------------------------
+// Scheduled daily restart
+// ---------------------------------------------------------------------------
+//
+// Always-on installs can ask for a nightly fresh start: at the configured
+// local time the backend is restarted (or the whole app, if requested), which
+// bounds slow memory growth of the Python process over multi-day uptimes.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone};
+use tauri::{AppHandle, Runtime};
+
+use crate::{backend, detached, heartbeat, instance, settings};
+
+/// How often the timer thread compares the clock with the schedule.
+const CHECK_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Parse a `HH:MM` local time.
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+/// Next instant strictly after *now* whose local time is *at*.
+pub fn next_occurrence(now: DateTime<Local>, at: NaiveTime) -> DateTime<Local> {
+    let mut day = now.date_naive();
+    loop {
+        // `earliest` handles DST folds; a time skipped by DST moves to the next day.
+        if let Some(candidate) = Local.from_local_datetime(&day.and_time(at)).earliest() {
+            if candidate > now {
+                return candidate;
+            }
+        }
+        day = day + Days::new(1);
+    }
+}
+
+/// Next scheduled restart according to the current settings.
+fn next_restart<R: Runtime>(app: &AppHandle<R>) -> Option<DateTime<Local>> {
+    let at = settings::current(app).scheduled_restart.as_deref().and_then(parse_time)?;
+    Some(next_occurrence(Local::now(), at))
+}
+
+/// Timer thread firing the scheduled restart. The target is re-armed whenever
+/// the configured time changes, and after each restart.
+pub fn start_timer<R: Runtime>(app: AppHandle<R>) {
+    let heartbeat = heartbeat::register(&app, "restart-timer", CHECK_INTERVAL);
+    let _ = std::thread::Builder::new().name("restart-timer".into()).spawn(move || {
+        let mut armed: Option<(NaiveTime, DateTime<Local>)> = None;
+        loop {
+            heartbeat.beat();
+            std::thread::sleep(CHECK_INTERVAL);
+            let current = settings::current(&app);
+            let Some(at) = current.scheduled_restart.as_deref().and_then(parse_time) else {
+                armed = None;
+                continue;
+            };
+            let now = Local::now();
+            let target = match armed {
+                Some((armed_at, target)) if armed_at == at => target,
+                _ => next_occurrence(now, at),
+            };
+            if now < target {
+                armed = Some((at, target));
+                continue;
+            }
+            armed = Some((at, next_occurrence(now, at)));
+            if current.scheduled_restart_app {
+                detached::on_exit(&app);
+                instance::release();
+                app.restart();
+            }
+            // An automatic restart like the health monitor's: skipped, with the
+            // backend left running, once the session budget is spent.
+            if backend::take_restart_token(&app) {
+                backend::restart_or_show_error(&app);
+            }
+        }
+    });
+}
+
+/// Enable (`time` = `"HH:MM"`) or disable (`time` = null) the daily restart.
+/// With `restart_app`, the whole application restarts instead of the backend
+/// only. Returns the next scheduled restart as RFC 3339, if any.
+#[tauri::command]
+pub fn set_scheduled_restart(
+    app: AppHandle,
+    time: Option<String>,
+    restart_app: bool,
+) -> Result<Option<String>, String> {
+    if let Some(text) = &time {
+        parse_time(text).ok_or_else(|| format!("Heure invalide « {text} » (attendu HH:MM)"))?;
+    }
+    settings::update(&app, |s| {
+        s.scheduled_restart = time.map(|t| t.trim().to_string());
+        s.scheduled_restart_app = restart_app;
+    })?;
+    Ok(next_scheduled_restart(app))
+}
+
+/// Next scheduled restart as RFC 3339, or null when disabled.
+#[tauri::command]
+pub fn next_scheduled_restart(app: AppHandle) -> Option<String> {
+    next_restart(&app).map(|at| at.to_rfc3339())
+}
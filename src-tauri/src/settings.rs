@@ -0,0 +1,137 @@
+// ---------------------------------------------------------------------------
+// Persisted user settings (settings.json in the app config dir)
+// ---------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime};
+
+use crate::detached::ExitMode;
+use crate::gpu::GpuPreference;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// User-tunable settings. Every field has a default so that an older or
+/// partially hand-edited `settings.json` still loads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Opacity of the main window, in [0.2, 1.0] (see `opacity.rs`).
+    pub opacity: f64,
+    /// Seconds the backend may keep accepting TCP without answering HTTP
+    /// before the health monitor treats it as hung and restarts it.
+    pub hang_restart_secs: u64,
+    /// Local `HH:MM` time of the daily restart, `None` when disabled.
+    pub scheduled_restart: Option<String>,
+    /// Restart the whole app at the scheduled time, not just the backend.
+    pub scheduled_restart_app: bool,
+    /// Automatic backend (re)starts allowed per app session.
+    pub restart_budget: u32,
+    /// Milliseconds the backend must stay unreachable before the reconnect
+    /// banner is shown; shorter blips recover silently.
+    pub reconnect_grace_ms: u64,
+    /// Custom webview user-agent; `None` appends `Tablerreur/{version}` to
+    /// the engine's own UA.
+    pub user_agent: Option<String>,
+    /// GPU the webview should run on, applied at launch (see `gpu.rs`).
+    pub gpu_preference: GpuPreference,
+    /// Backend worker threads, `None` to keep the backend's own default.
+    pub backend_concurrency: Option<u32>,
+    /// Worker threads while on battery; `None` disables the power monitor's
+    /// adjustment (see `concurrency.rs`).
+    pub battery_concurrency: Option<u32>,
+    /// `kill` the backend when the app closes, or `detach` it so the next
+    /// launch reconnects to it (see `detached.rs`).
+    pub on_exit: ExitMode,
+    /// Data files opened through `open_workspace`, most recent first.
+    pub recent_workspaces: Vec<String>,
+    /// Soft quota of the webview disk cache in MB, `None` for no limit.
+    pub webview_cache_quota_mb: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            hang_restart_secs: 30,
+            scheduled_restart: None,
+            scheduled_restart_app: false,
+            restart_budget: 5,
+            reconnect_grace_ms: 3000,
+            user_agent: None,
+            gpu_preference: GpuPreference::Auto,
+            backend_concurrency: None,
+            battery_concurrency: None,
+            on_exit: ExitMode::Kill,
+            recent_workspaces: Vec::new(),
+            webview_cache_quota_mb: None,
+        }
+    }
+}
+
+/// Managed state holding the live settings for the app lifetime.
+pub struct SettingsState(pub Mutex<Settings>);
+
+pub fn settings_path<R: Runtime>(app: &impl Manager<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+/// Read settings from disk, falling back to defaults if the file is missing
+/// or unreadable (a corrupt file must never prevent the app from starting).
+pub fn load<R: Runtime>(app: &impl Manager<R>) -> Settings {
+    settings_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Write *settings* to disk, creating the config directory if needed.
+pub fn save<R: Runtime>(app: &impl Manager<R>, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app).ok_or("Impossible de résoudre app_config_dir")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Apply *change* to the managed settings and persist the result.
+pub fn update<R: Runtime>(
+    app: &impl Manager<R>,
+    change: impl FnOnce(&mut Settings),
+) -> Result<Settings, String> {
+    let state = app.state::<SettingsState>();
+    let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+    change(&mut guard);
+    save(app, &guard)?;
+    Ok(guard.clone())
+}
+
+/// Snapshot of the current settings.
+pub fn current<R: Runtime>(app: &impl Manager<R>) -> Settings {
+    app.try_state::<SettingsState>()
+        .and_then(|state| state.0.lock().ok().map(|s| s.clone()))
+        .unwrap_or_default()
+}
+
+/// Where each effective setting comes from: `settings.json` when the key is
+/// present in the file, `défaut` otherwise.
+pub fn provenance<R: Runtime>(app: &impl Manager<R>) -> BTreeMap<String, &'static str> {
+    let in_file: serde_json::Map<String, serde_json::Value> = settings_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    let effective = serde_json::to_value(current(app)).unwrap_or_default();
+    effective
+        .as_object()
+        .into_iter()
+        .flat_map(|fields| fields.keys())
+        .map(|key| {
+            let source = if in_file.contains_key(key) { SETTINGS_FILE } else { "défaut" };
+            (key.clone(), source)
+        })
+        .collect()
+}
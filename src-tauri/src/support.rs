@@ -0,0 +1,256 @@
+// ---------------------------------------------------------------------------
+// Support package: one zip with everything needed to triage a report
+// ---------------------------------------------------------------------------
+
+use std::env::consts::{ARCH, OS};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::zip::ZipWriter;
+use crate::{backend, crash, diagnostics, disk, dump, health, http, settings};
+
+/// Only the end of each log file is packaged.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Larger backend dumps are left out of the package.
+const MAX_DUMP_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Timeout of each backend check request.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Backend paths requested for the package: the health probe, and the page
+/// the main window loads first.
+const CHECK_PATHS: &[&str] = &[health::HEALTH_PATH, "/"];
+
+/// Last *max* bytes of the file at *path*.
+fn read_tail(path: &Path, max: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max)))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Regular files directly inside *dir*, sorted by name.
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn system_info<R: Runtime>(app: &AppHandle<R>) -> String {
+    let dir = |d: tauri::Result<PathBuf>| d.map(|p| p.display().to_string()).unwrap_or_default();
+    format!(
+        "Tablerreur : {}\nTauri : {}\nSystème : {OS} {ARCH}\nFamille : {}\n\
+         Exécutable : {}\nConfig : {}\nDonnées : {}\nJournaux : {}\nLangue : {}\n",
+        app.package_info().version,
+        tauri::VERSION,
+        std::env::consts::FAMILY,
+        std::env::current_exe().map(|p| p.display().to_string()).unwrap_or_default(),
+        dir(app.path().app_config_dir()),
+        dir(app.path().app_data_dir()),
+        dir(app.path().app_log_dir()),
+        std::env::var("LC_ALL").or_else(|_| std::env::var("LANG")).unwrap_or_default(),
+    )
+}
+
+fn config_report<R: Runtime>(app: &AppHandle<R>) -> serde_json::Value {
+    json!({
+        "path": settings::settings_path(app),
+        "effective": settings::current(app),
+        "provenance": settings::provenance(app),
+        "backend_limits": backend::limits(),
+    })
+}
+
+/// Request each of CHECK_PATHS from the live backend and report the status
+/// and latency of each, with the body of the health probe.
+fn backend_checks<R: Runtime>(app: &AppHandle<R>) -> serde_json::Value {
+    let Some(port) = backend::current_port(app) else {
+        return json!({ "port": null, "error": "serveur non démarré" });
+    };
+    let checks: Vec<serde_json::Value> = CHECK_PATHS
+        .iter()
+        .map(|path| {
+            let started = Instant::now();
+            match http::get(port, path, CHECK_TIMEOUT) {
+                Ok(resp) => json!({
+                    "path": path,
+                    "status": resp.status,
+                    "latency_ms": started.elapsed().as_millis() as u64,
+                    "body": (*path == health::HEALTH_PATH).then(|| resp.text()),
+                }),
+                Err(e) => json!({ "path": path, "error": e.to_string() }),
+            }
+        })
+        .collect();
+    json!({ "port": port, "checks": checks })
+}
+
+/// Best-effort PNG capture of the main window's screen area with the
+/// platform's own tool; only the window region is captured.
+fn capture_window<R: Runtime>(app: &AppHandle<R>, dest: &Path) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("fenêtre principale absente")?;
+    let pos = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let (x, y, w, h) = (pos.x, pos.y, size.width, size.height);
+    let out = dest.display().to_string();
+
+    let status = if cfg!(target_os = "macos") {
+        // screencapture works in points, Tauri reports physical pixels.
+        let scale = window.scale_factor().unwrap_or(1.0);
+        let rect = format!(
+            "-R{},{},{},{}",
+            (x as f64 / scale).round(),
+            (y as f64 / scale).round(),
+            (w as f64 / scale).round(),
+            (h as f64 / scale).round()
+        );
+        Command::new("screencapture").args(["-x", &rect, &out]).status()
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName System.Drawing; \
+             $b = New-Object System.Drawing.Bitmap {w}, {h}; \
+             [System.Drawing.Graphics]::FromImage($b).CopyFromScreen({x}, {y}, 0, 0, $b.Size); \
+             $b.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+            out.replace('\'', "''")
+        );
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", &script]);
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        command.status()
+    } else {
+        Command::new("grim")
+            .args(["-g", &format!("{x},{y} {w}x{h}"), &out])
+            .status()
+            .or_else(|_| {
+                Command::new("import")
+                    .args(["-window", "root", "-crop", &format!("{w}x{h}+{x}+{y}"), &out])
+                    .status()
+            })
+    };
+    match status {
+        Ok(s) if s.success() && dest.exists() => Ok(()),
+        Ok(s) => Err(format!("l'outil de capture a échoué ({s})")),
+        Err(e) => Err(format!("outil de capture indisponible : {e}")),
+    }
+}
+
+/// Gather logs, crash reports, config, system info, backend checks,
+/// network environment, recent health probes and a screenshot into a zip;
+/// returns its path.
+/// A partially written package is removed, and a full disk is reported.
+pub fn create_package<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let now = chrono::Local::now();
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Impossible de résoudre app_data_dir : {e}"))?
+        .join("support");
+    let zip_path = dir.join(format!("tablerreur-support-{}.zip", now.format("%Y%m%d-%H%M%S")));
+    match write_package(app, &dir, &zip_path, now) {
+        Ok(()) => Ok(zip_path),
+        Err(e) => {
+            let _ = std::fs::remove_file(&zip_path);
+            if disk::is_full(&e) {
+                disk::report(app, "support");
+                return Err(format!("Disque plein, paquet non créé ({})", dir.display()));
+            }
+            Err(format!("{}: {e}", zip_path.display()))
+        }
+    }
+}
+
+fn write_package<R: Runtime>(
+    app: &AppHandle<R>,
+    dir: &Path,
+    zip_path: &Path,
+    now: chrono::DateTime<chrono::Local>,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let file = std::fs::File::create(zip_path)?;
+    let mut zip = ZipWriter::new(std::io::BufWriter::new(file), now.naive_local());
+    let mut manifest = vec![format!("Paquet de support Tablerreur — {}", now.to_rfc3339())];
+    let mut add = |zip: &mut ZipWriter<_>, name: &str, data: &[u8]| {
+        manifest.push(format!("{name} ({} octets)", data.len()));
+        zip.add(name, data)
+    };
+
+    add(&mut zip, "system.txt", system_info(app).as_bytes())?;
+    let pretty = |v: &serde_json::Value| serde_json::to_vec_pretty(v).unwrap_or_default();
+    add(&mut zip, "config.json", &pretty(&config_report(app)))?;
+    add(&mut zip, "backend-checks.json", &pretty(&backend_checks(app)))?;
+    let network = serde_json::to_value(diagnostics::network_report(app)).unwrap_or_default();
+    add(&mut zip, "network.json", &pretty(&network))?;
+    let probes = serde_json::to_value(health::probe_history(app)).unwrap_or_default();
+    add(&mut zip, "health-probes.json", &pretty(&probes))?;
+
+    if let Ok(log_dir) = app.path().app_log_dir() {
+        for path in files_in(&log_dir) {
+            if let (Some(name), Ok(data)) = (path.file_name(), read_tail(&path, MAX_LOG_BYTES)) {
+                add(&mut zip, &format!("logs/{}", name.to_string_lossy()), &data)?;
+            }
+        }
+    }
+    if let Some(crash_dir) = crash::reports_dir(app) {
+        for path in files_in(&crash_dir) {
+            if let (Some(name), Ok(data)) = (path.file_name(), std::fs::read(&path)) {
+                add(&mut zip, &format!("crashes/{}", name.to_string_lossy()), &data)?;
+            }
+        }
+    }
+
+    let mut dump_note = None;
+    if let Some(path) = dump::latest(app) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        match std::fs::metadata(&path) {
+            Ok(meta) if meta.len() > MAX_DUMP_BYTES => {
+                dump_note = Some(format!("État du serveur non inclus (trop volumineux) : {}", path.display()));
+            }
+            Ok(_) => {
+                if let Ok(data) = std::fs::read(&path) {
+                    add(&mut zip, &format!("dumps/{name}"), &data)?;
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    let shot = dir.join(".screenshot.png");
+    let shot_note = match capture_window(app, &shot).and_then(|()| {
+        std::fs::read(&shot).map_err(|e| e.to_string())
+    }) {
+        Ok(data) => add(&mut zip, "screenshot.png", &data).map(|()| None)?,
+        Err(e) => Some(format!("Capture d'écran non incluse : {e}")),
+    };
+    let _ = std::fs::remove_file(&shot);
+
+    manifest.extend(dump_note);
+    manifest.extend(shot_note);
+    manifest.push(String::new());
+    let manifest = manifest.join("\n");
+    zip.add("manifest.txt", manifest.as_bytes())?;
+    // Flush and sync explicitly: dropping the BufWriter would swallow a
+    // late ENOSPC and leave a truncated zip behind.
+    let file = zip.finish()?.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()
+}
+
+/// Build the support package and return its path.
+#[tauri::command]
+pub async fn create_support_package(app: AppHandle) -> Result<String, String> {
+    create_package(&app).map(|path| path.display().to_string())
+}
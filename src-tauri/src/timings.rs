@@ -0,0 +1,176 @@
+// ---------------------------------------------------------------------------
+// Startup timings, split between cold and warm starts
+// ---------------------------------------------------------------------------
+//
+// A start is "cold" when the warm-start marker is missing or was written by
+// another version: first launch, or first launch after an update, when the
+// backend initialises from scratch. Each category keeps its own history in
+// start-timings.json so perf changes can be judged on cold starts, the ones
+// users feel most.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+const MARKER_FILE: &str = "warm-start.marker";
+const HISTORY_FILE: &str = "start-timings.json";
+const TRACE_FILE: &str = "startup-trace.json";
+
+/// Starts kept per category.
+const HISTORY_LEN: usize = 20;
+
+/// Facts about the current launch, captured before the backend is spawned.
+pub struct LaunchInfo {
+    pub started: Instant,
+    pub cold: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartSample {
+    pub at: String,
+    pub duration_ms: u64,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartTimings {
+    /// Cold starts, oldest first.
+    pub cold: VecDeque<StartSample>,
+    /// Warm starts, oldest first.
+    pub warm: VecDeque<StartSample>,
+}
+
+fn data_file<R: Runtime>(app: &impl Manager<R>, name: &str) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(name))
+}
+
+fn version<R: Runtime>(app: &impl Manager<R>) -> String {
+    app.package_info().version.to_string()
+}
+
+/// Detect whether this launch is cold, from the marker left by the last start.
+pub fn detect<R: Runtime>(app: &impl Manager<R>, started: Instant) -> LaunchInfo {
+    let marker = data_file(app, MARKER_FILE).and_then(|p| std::fs::read_to_string(p).ok());
+    let cold = marker.as_deref().map(str::trim) != Some(version(app).as_str());
+    LaunchInfo { started, cold }
+}
+
+fn load<R: Runtime>(app: &impl Manager<R>) -> StartTimings {
+    data_file(app, HISTORY_FILE)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Record the time from launch to a healthy backend, then write the marker
+/// so the next launch counts as warm.
+pub fn record_ready<R: Runtime>(app: &AppHandle<R>) {
+    let Some(launch) = app.try_state::<LaunchInfo>() else {
+        return;
+    };
+    let (Some(history_path), Some(marker_path)) =
+        (data_file(app, HISTORY_FILE), data_file(app, MARKER_FILE))
+    else {
+        return;
+    };
+    let mut timings = load(app);
+    let samples = if launch.cold { &mut timings.cold } else { &mut timings.warm };
+    samples.push_back(StartSample {
+        at: chrono::Local::now().to_rfc3339(),
+        duration_ms: launch.started.elapsed().as_millis() as u64,
+        version: version(app),
+    });
+    while samples.len() > HISTORY_LEN {
+        samples.pop_front();
+    }
+    if let Some(dir) = history_path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&timings) {
+        let _ = std::fs::write(history_path, json);
+    }
+    let _ = std::fs::write(marker_path, version(app));
+}
+
+/// Recent cold and warm start durations.
+#[tauri::command]
+pub fn start_timings(app: AppHandle) -> StartTimings {
+    load(&app)
+}
+
+// ---------------------------------------------------------------------------
+// Startup trace: timestamped phases of this launch, for deep debugging
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    /// Milliseconds since the process started.
+    pub t_ms: u64,
+    pub at: String,
+    pub phase: &'static str,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    pub detail: serde_json::Value,
+}
+
+/// Managed trace of the current launch, in order.
+#[derive(Default)]
+pub struct StartupTrace(Mutex<Vec<TraceEvent>>);
+
+/// Append *phase* to the startup trace.
+pub fn trace<R: Runtime>(app: &impl Manager<R>, phase: &'static str, detail: serde_json::Value) {
+    let (Some(launch), Some(trace)) = (app.try_state::<LaunchInfo>(), app.try_state::<StartupTrace>())
+    else {
+        return;
+    };
+    let event = TraceEvent {
+        t_ms: launch.started.elapsed().as_millis() as u64,
+        at: chrono::Local::now().to_rfc3339(),
+        phase,
+        detail,
+    };
+    if let Ok(mut events) = trace.0.lock() {
+        events.push(event);
+    };
+}
+
+/// Whether *phase* was already traced during this launch.
+pub fn traced<R: Runtime>(app: &impl Manager<R>, phase: &str) -> bool {
+    app.try_state::<StartupTrace>()
+        .and_then(|trace| trace.0.lock().ok().map(|e| e.iter().any(|ev| ev.phase == phase)))
+        .unwrap_or(false)
+}
+
+/// Write the trace to `startup-trace.json` in the log dir (so support
+/// packages pick it up) and return its path.
+pub fn write_trace<R: Runtime>(app: &impl Manager<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Impossible de résoudre app_log_dir : {e}"))?;
+    let launch = app.try_state::<LaunchInfo>();
+    let events = app
+        .try_state::<StartupTrace>()
+        .and_then(|trace| trace.0.lock().ok().map(|e| e.clone()))
+        .unwrap_or_default();
+    let report = serde_json::json!({
+        "version": version(app),
+        "cold": launch.map(|l| l.cold),
+        "events": events,
+    });
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let path = dir.join(TRACE_FILE);
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Write the startup trace of this launch and return the file path.
+#[tauri::command]
+pub fn export_startup_trace(app: AppHandle) -> Result<String, String> {
+    write_trace(&app).map(|path| path.display().to_string())
+}
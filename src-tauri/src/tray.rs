@@ -0,0 +1,160 @@
+// ---------------------------------------------------------------------------
+// System tray: the app keeps running with its window closed
+// ---------------------------------------------------------------------------
+//
+// With the tray icon in place, closing the main window only hides it; the
+// backend is stopped by an explicit Quitter (tray or Fichier menu). Where no
+// tray can be created (a Linux desktop without a status area) closing the
+// window still quits, or the app would be left running with no way back.
+//
+// Every quit first asks the backend whether it holds unsaved work
+// (`GET /dirty`) and confirms before losing it. An unreachable backend
+// skips the question: a dead server must not keep the user from quitting.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::{backend, detached, geometry, http, i18n};
+
+const TRAY_ID: &str = "main";
+
+/// Timeout of the `GET /dirty` request made before quitting.
+const DIRTY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Set while a quit confirmation is on screen, so a second Cmd+Q does not
+/// stack another dialog.
+static CONFIRMING: AtomicBool = AtomicBool::new(false);
+
+/// Bring the hidden or minimized main window back and focus it.
+pub fn show_main<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn hide_main<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = geometry::save(&window);
+        let _ = window.hide();
+    }
+}
+
+/// Explicit quit: save the window geometry, stop or detach the backend, exit.
+pub fn quit<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = geometry::save(&window);
+        }
+    }
+    detached::on_exit(app);
+    app.exit(0);
+}
+
+/// Whether the backend reports unsaved work: a JSON boolean, or an object
+/// with a `dirty` field. False when it cannot be asked.
+fn has_unsaved_work<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let Some(port) = backend::current_port(app) else {
+        return false;
+    };
+    let Ok(resp) = http::get(port, "/dirty", DIRTY_TIMEOUT) else {
+        return false;
+    };
+    if resp.status != 200 {
+        return false;
+    }
+    serde_json::from_str::<serde_json::Value>(&resp.text())
+        .ok()
+        .and_then(|value| value.as_bool().or_else(|| value.get("dirty")?.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Quit, after confirmation if the backend has unsaved work. Returns at once:
+/// the check and the dialog run on their own thread, never in the caller's
+/// event handler.
+pub fn confirm_quit<R: Runtime>(app: &AppHandle<R>) {
+    if CONFIRMING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if !has_unsaved_work(&app) {
+            CONFIRMING.store(false, Ordering::SeqCst);
+            quit(&app);
+            return;
+        }
+        let text = i18n::strings();
+        let handle = app.clone();
+        app.dialog()
+            .message(text.quit_unsaved)
+            .title(text.quit_unsaved_title)
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                text.menu_quit.to_string(),
+                text.cancel.to_string(),
+            ))
+            .show(move |confirmed| {
+                CONFIRMING.store(false, Ordering::SeqCst);
+                if confirmed {
+                    quit(&handle);
+                }
+            });
+    });
+}
+
+/// Whether the tray icon exists, so that closing the window can hide it.
+pub fn active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+/// Create the tray icon and its Afficher / Masquer / Quitter menu.
+pub fn build<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let text = i18n::strings();
+    let show = MenuItemBuilder::new(text.tray_show).id("tray-show").build(app)?;
+    let hide = MenuItemBuilder::new(text.tray_hide).id("tray-hide").build(app)?;
+    let quit_item = MenuItemBuilder::new(text.menu_quit).id("tray-quit").build(app)?;
+    let menu = MenuBuilder::new(app).item(&show).item(&hide).separator().item(&quit_item).build()?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Tablerreur")
+        .menu(&menu)
+        // Left click restores the window; the menu stays on right click.
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "tray-show" => show_main(app),
+            "tray-hide" => hide_main(app),
+            "tray-quit" => confirm_quit(app),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Close button of the main window: hide it to the tray when there is one.
+/// Returns false when the close should proceed (no tray).
+pub fn hide_on_close<R: Runtime>(app: &AppHandle<R>) -> bool {
+    if !active(app) {
+        return false;
+    }
+    hide_main(app);
+    true
+}
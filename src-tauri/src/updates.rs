@@ -0,0 +1,171 @@
+// ---------------------------------------------------------------------------
+// Update check: Aide > Vérifier les mises à jour
+// ---------------------------------------------------------------------------
+//
+// The latest GitHub release is fetched with the system `curl` (HTTPS, which
+// the std-only client in http.rs does not speak) and its `tag_name` compared
+// with the running version. The releases page stays the fallback: it is
+// offered whenever the check itself fails.
+
+use std::process::Command;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::i18n;
+
+const RELEASES_PAGE: &str = "https://github.com/Hsbtqemy/Tablerreur/releases";
+
+const LATEST_RELEASE_API: &str =
+    "https://api.github.com/repos/Hsbtqemy/Tablerreur/releases/latest";
+
+/// Upper bound on the whole request, so the check can never hang.
+const CHECK_TIMEOUT_SECS: &str = "15";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: Option<String>,
+}
+
+fn fetch_latest<R: Runtime>(app: &AppHandle<R>) -> Result<Release, String> {
+    let agent = format!("User-Agent: Tablerreur/{}", app.package_info().version);
+    let mut cmd = Command::new("curl");
+    cmd.args(["-fsSL", "--max-time", CHECK_TIMEOUT_SECS])
+        .args(["-H", "Accept: application/vnd.github+json", "-H", &agent, LATEST_RELEASE_API]);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let text = i18n::strings();
+    let output = cmd.output().map_err(|e| format!("{} {e}", text.curl_unavailable))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} {}", text.request_failed, stderr.trim()));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("{} {e}", text.unexpected_response))
+}
+
+/// `v1.2.3` or `1.2.3-beta` as (major, minor, patch); missing parts are 0.
+fn parse_version(tag: &str) -> Option<(u64, u64, u64)> {
+    let core = tag.trim().trim_start_matches(['v', 'V']);
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+fn open_page<R: Runtime>(app: &AppHandle<R>, url: &str) {
+    let _ = app.opener().open_url(url, None::<&str>);
+}
+
+/// Check for a newer release and report the result in a native dialog.
+/// Blocking: call it off the UI thread.
+pub fn check<R: Runtime>(app: &AppHandle<R>) {
+    let text = i18n::strings();
+    let current = &app.package_info().version;
+    let dialog = app.dialog();
+    let outcome = fetch_latest(app).and_then(|release| {
+        parse_version(&release.tag_name)
+            .map(|latest| (release, latest))
+            .ok_or_else(|| text.unreadable_version.to_string())
+    });
+    match outcome {
+        Ok((release, latest)) if latest > (current.major, current.minor, current.patch) => {
+            let url = release.html_url.unwrap_or_else(|| RELEASES_PAGE.to_string());
+            let handle = app.clone();
+            dialog
+                .message(format!(
+                    "{}\n\n{} : {}\n{} : {current}",
+                    text.update_available,
+                    text.latest_version,
+                    release.tag_name.trim_start_matches(['v', 'V']),
+                    text.installed_version
+                ))
+                .title(text.update_available_title)
+                .kind(MessageDialogKind::Info)
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    text.download.to_string(),
+                    text.later.to_string(),
+                ))
+                .show(move |download| {
+                    if download {
+                        open_page(&handle, &url);
+                    }
+                });
+        }
+        Ok(_) => {
+            dialog
+                .message(format!("{}\n\n{} : {current}", text.up_to_date, text.installed_version))
+                .title(text.updates_title)
+                .kind(MessageDialogKind::Info)
+                .show(|_| {});
+        }
+        Err(e) => {
+            let handle = app.clone();
+            dialog
+                .message(format!("{}\n{e}\n\n{}", text.update_check_failed, text.open_releases))
+                .title(text.updates_title)
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    text.open_page.to_string(),
+                    text.close.to_string(),
+                ))
+                .show(move |open| {
+                    if open {
+                        open_page(&handle, RELEASES_PAGE);
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_v_prefix() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("V0.10.0"), Some((0, 10, 0)));
+        assert_eq!(parse_version(" 2.0.1\n"), Some((2, 0, 1)));
+    }
+
+    #[test]
+    fn ignores_prerelease_and_build_suffixes() {
+        assert_eq!(parse_version("v1.2.3-beta"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3-rc.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3+build.7"), Some((1, 2, 3)));
+        // Not newer than the release it precedes.
+        assert!(parse_version("v1.2.3-beta") <= parse_version("1.2.3"));
+    }
+
+    #[test]
+    fn fills_missing_parts_with_zero() {
+        assert_eq!(parse_version("v1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("3"), Some((3, 0, 0)));
+        assert_eq!(parse_version("v1.2-beta"), Some((1, 2, 0)));
+    }
+
+    #[test]
+    fn compares_numerically() {
+        assert!(parse_version("v0.10.0") > parse_version("v0.9.9"));
+        assert!(parse_version("1.2.10") > parse_version("1.2.9"));
+    }
+
+    #[test]
+    fn rejects_unreadable_tags() {
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("v"), None);
+        assert_eq!(parse_version("latest"), None);
+        assert_eq!(parse_version("release-1.2.3"), None);
+        assert_eq!(parse_version("1.x.3"), None);
+        assert_eq!(parse_version("1..3"), None);
+    }
+}
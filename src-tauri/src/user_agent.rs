@@ -0,0 +1,126 @@
+// ---------------------------------------------------------------------------
+// Webview user-agent
+// ---------------------------------------------------------------------------
+//
+// Tauri only takes a user-agent at window creation, so the live webview is
+// reconfigured through the engine's own settings instead (WebKitGTK settings,
+// WebView2 ICoreWebView2Settings2, WKWebView customUserAgent). By default the
+// engine's UA gets a `Tablerreur/{version}` token appended so the backend can
+// tell the desktop client from a browser.
+//
+// Once a custom UA is set, the engine reports it back as its own, so the
+// engine's original UA is captured on the first apply (at `restore`) and every
+// later default is built from that copy.
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings;
+
+/// WKWebView's UA, which Safari froze; WebKit offers no public getter for it.
+#[cfg(target_os = "macos")]
+const MACOS_BASE_UA: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko)";
+
+/// The engine's own UA, as it was before we first changed it.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+static ENGINE_UA: OnceLock<String> = OnceLock::new();
+
+/// `Tablerreur/{version}` token identifying the desktop client.
+fn token() -> String {
+    format!("Tablerreur/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// *base* with our token appended exactly once.
+pub fn with_token(base: &str) -> String {
+    let kept: Vec<&str> = base
+        .split_whitespace()
+        .filter(|part| !part.starts_with("Tablerreur/"))
+        .collect();
+    let mut ua = kept.join(" ");
+    if !ua.is_empty() {
+        ua.push(' ');
+    }
+    ua.push_str(&token());
+    ua
+}
+
+/// Apply *custom* (or, when `None`, the engine UA plus our token) to *window*.
+pub fn apply<R: Runtime>(window: &WebviewWindow<R>, custom: Option<String>) -> Result<(), String> {
+    window
+        .with_webview(move |webview| platform_apply(webview, custom))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_apply(webview: tauri::webview::PlatformWebview, custom: Option<String>) {
+    use webkit2gtk::{SettingsExt, WebViewExt};
+    if let Some(settings) = WebViewExt::settings(&webview.inner()) {
+        let base = ENGINE_UA
+            .get_or_init(|| settings.user_agent().map(|ua| ua.to_string()).unwrap_or_default());
+        let ua = custom.unwrap_or_else(|| with_token(base));
+        settings.set_user_agent(Some(&ua));
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_apply(webview: tauri::webview::PlatformWebview, custom: Option<String>) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Settings2;
+    use windows::core::{Interface, HSTRING, PWSTR};
+    // SAFETY: COM calls on the live controller, made on the webview's thread.
+    unsafe {
+        let Ok(core) = webview.controller().CoreWebView2() else {
+            return;
+        };
+        let Ok(settings) = core.Settings().and_then(|s| s.cast::<ICoreWebView2Settings2>()) else {
+            return;
+        };
+        let base = ENGINE_UA.get_or_init(|| {
+            let mut current = PWSTR::null();
+            match settings.UserAgent(&mut current) {
+                Ok(()) => webview2_com::take_pwstr(current),
+                Err(_) => String::new(),
+            }
+        });
+        let ua = custom.unwrap_or_else(|| with_token(base));
+        let _ = settings.SetUserAgent(&HSTRING::from(ua));
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_apply(webview: tauri::webview::PlatformWebview, custom: Option<String>) {
+    use objc2::{msg_send, runtime::AnyObject};
+    use objc2_foundation::NSString;
+    let ua = custom.unwrap_or_else(|| with_token(MACOS_BASE_UA));
+    let ua = NSString::from_str(&ua);
+    // SAFETY: inner() is the live WKWebView; with_webview runs on the main thread.
+    unsafe {
+        let wk_webview = &*(webview.inner() as *const AnyObject);
+        let _: () = msg_send![wk_webview, setCustomUserAgent: &*ua];
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn platform_apply(_webview: tauri::webview::PlatformWebview, _custom: Option<String>) {}
+
+/// Set the user-agent of the main webview and persist it. An empty *ua*
+/// restores the default (engine UA + `Tablerreur/{version}`).
+#[tauri::command]
+pub fn set_user_agent(app: AppHandle, ua: String) -> Result<(), String> {
+    let custom = Some(ua.trim().to_string()).filter(|ua| !ua.is_empty());
+    settings::update(&app, |s| s.user_agent = custom.clone())?;
+    if let Some(window) = app.get_webview_window("main") {
+        apply(&window, custom)?;
+    }
+    Ok(())
+}
+
+/// Apply the persisted user-agent to the main window at startup. Being the
+/// first apply, this is also where the engine's original UA is captured.
+pub fn restore<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = apply(&window, settings::current(app).user_agent);
+    }
+}
@@ -0,0 +1,134 @@
+// ---------------------------------------------------------------------------
+// Workspace switching: point the backend at another data file, live
+// ---------------------------------------------------------------------------
+//
+// The backend switches its active data file through
+// `POST /admin/open-workspace`; the main window is then reloaded. Every
+// successfully opened file goes to the top of the Fichier > Récents menu.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::menu::{MenuItemBuilder, Submenu};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::{backend, http, i18n, settings};
+
+/// Data files the backend can open (mirrors `_ALLOWED_EXTENSIONS`).
+const WORKSPACE_EXTENSIONS: &[&str] = &["csv", "xlsx", "xls", "xlsm"];
+
+/// Entries kept in the recent files menu.
+const MAX_RECENT: usize = 10;
+
+const OPEN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Menu id prefix of the recent files entries, followed by their index.
+const RECENT_PREFIX: &str = "recent-workspace:";
+
+/// The Fichier > Récents submenu, rebuilt whenever the list changes.
+pub struct RecentMenu<R: Runtime>(pub Submenu<R>);
+
+fn validate(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Fichier introuvable : {path} ({e})"))?;
+    if !path.is_file() {
+        return Err(format!("{} n'est pas un fichier", path.display()));
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    if !WORKSPACE_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!(
+            "Format non pris en charge : {} (attendu : {})",
+            path.display(),
+            WORKSPACE_EXTENSIONS.join(", ")
+        ));
+    }
+    Ok(path)
+}
+
+/// Rebuild the recent files submenu from the settings.
+pub fn refresh_menu<R: Runtime>(app: &AppHandle<R>) {
+    let Some(menu) = app.try_state::<RecentMenu<R>>() else {
+        return;
+    };
+    let submenu = &menu.0;
+    while let Ok(Some(_)) = submenu.remove_at(0) {}
+    let recent = settings::current(app).recent_workspaces;
+    if recent.is_empty() {
+        if let Ok(item) = MenuItemBuilder::new(i18n::strings().menu_no_recent).enabled(false).build(app) {
+            let _ = submenu.append(&item);
+        }
+    }
+    for (i, path) in recent.iter().enumerate() {
+        if let Ok(item) = MenuItemBuilder::new(path).id(format!("{RECENT_PREFIX}{i}")).build(app) {
+            let _ = submenu.append(&item);
+        }
+    }
+}
+
+/// Switch the backend to the data file at *path* and reload the main window.
+pub fn open<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<(), String> {
+    let path = validate(path)?;
+    let text = i18n::strings();
+    let port = backend::current_port(app).ok_or(text.server_not_started)?;
+    let display = path.display().to_string();
+    let body = serde_json::json!({ "path": display }).to_string();
+    let resp = http::post(port, "/admin/open-workspace", body.as_bytes(), OPEN_TIMEOUT)
+        .map_err(|e| format!("{} {e}", text.server_unreachable))?;
+    match resp.status {
+        200..=299 => {}
+        404 | 405 | 501 => return Err(text.workspace_unsupported.to_string()),
+        status => return Err(format!("{} (HTTP {status}) : {}", text.workspace_refused, resp.text())),
+    }
+    settings::update(app, |s| {
+        s.recent_workspaces.retain(|p| *p != display);
+        s.recent_workspaces.insert(0, display.clone());
+        s.recent_workspaces.truncate(MAX_RECENT);
+    })?;
+    refresh_menu(app);
+    backend::mark_ready(app, port);
+    Ok(())
+}
+
+fn open_or_report<R: Runtime>(app: &AppHandle<R>, path: &str) {
+    if let Err(e) = open(app, path) {
+        app.dialog()
+            .message(e)
+            .title(i18n::strings().workspace_title)
+            .kind(MessageDialogKind::Error)
+            .show(|_| {});
+    }
+}
+
+/// Handle the workspace menu entries; false if *id* is not one of them.
+pub fn handle_menu<R: Runtime>(app: &AppHandle<R>, id: &str) -> bool {
+    if id == "open-workspace" {
+        let text = i18n::strings();
+        let handle = app.clone();
+        app.dialog()
+            .file()
+            .set_title(text.workspace_title)
+            .add_filter(text.workspace_filter, WORKSPACE_EXTENSIONS)
+            .pick_file(move |file| {
+                if let Some(path) = file.and_then(|f| f.into_path().ok()) {
+                    std::thread::spawn(move || open_or_report(&handle, &path.to_string_lossy()));
+                }
+            });
+        return true;
+    }
+    let Some(index) = id.strip_prefix(RECENT_PREFIX).and_then(|i| i.parse::<usize>().ok()) else {
+        return false;
+    };
+    if let Some(path) = settings::current(app).recent_workspaces.get(index).cloned() {
+        let app = app.clone();
+        std::thread::spawn(move || open_or_report(&app, &path));
+    }
+    true
+}
+
+/// Open the data file at *path* in the running backend.
+#[tauri::command]
+pub async fn open_workspace(app: AppHandle, path: String) -> Result<(), String> {
+    open(&app, &path)
+}
@@ -0,0 +1,180 @@
+// ---------------------------------------------------------------------------
+// Minimal ZIP writer (stored entries, no compression) — avoids adding an
+// external crate for the handful of text files of a support package.
+// ---------------------------------------------------------------------------
+
+use std::io::{self, Write};
+
+/// CRC-32 (IEEE 802.3), as required by the ZIP format.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+pub struct ZipWriter<W: Write> {
+    out: W,
+    entries: Vec<Entry>,
+    offset: u32,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl<W: Write> ZipWriter<W> {
+    /// Start an archive; every entry gets the given local date and time.
+    pub fn new(out: W, modified: chrono::NaiveDateTime) -> Self {
+        use chrono::{Datelike, Timelike};
+        let dos_time = ((modified.hour() << 11) | (modified.minute() << 5) | (modified.second() / 2)) as u16;
+        let year = modified.year().clamp(1980, 2107) as u32 - 1980;
+        let dos_date = ((year << 9) | (modified.month() << 5) | modified.day()) as u16;
+        Self { out, entries: Vec::new(), offset: 0, dos_time, dos_date }
+    }
+
+    fn too_large() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, "archive ZIP trop volumineuse")
+    }
+
+    /// Append a file named *name* (forward slashes for folders).
+    pub fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = u32::try_from(data.len()).map_err(|_| Self::too_large())?;
+        let crc = crc32(data);
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        header.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&self.dos_time.to_le_bytes());
+        header.extend_from_slice(&self.dos_date.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        header.extend_from_slice(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(data)?;
+        self.entries.push(Entry { name: name.to_string(), crc, size, offset: self.offset });
+        self.offset = self
+            .offset
+            .checked_add(header.len() as u32)
+            .and_then(|o| o.checked_add(size))
+            .ok_or_else(Self::too_large)?;
+        Ok(())
+    }
+
+    /// Write the central directory and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut central = Vec::new();
+        for entry in &self.entries {
+            central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            central.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            central.extend_from_slice(&0x0800u16.to_le_bytes());
+            central.extend_from_slice(&0u16.to_le_bytes());
+            central.extend_from_slice(&self.dos_time.to_le_bytes());
+            central.extend_from_slice(&self.dos_date.to_le_bytes());
+            central.extend_from_slice(&entry.crc.to_le_bytes());
+            central.extend_from_slice(&entry.size.to_le_bytes());
+            central.extend_from_slice(&entry.size.to_le_bytes());
+            central.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            central.extend_from_slice(&entry.offset.to_le_bytes());
+            central.extend_from_slice(entry.name.as_bytes());
+        }
+        let count = self.entries.len() as u16;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // disk numbers
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        end.extend_from_slice(&self.offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.out.write_all(&central)?;
+        self.out.write_all(&end)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([data[at], data[at + 1]])
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn writes_a_readable_two_entry_archive() {
+        let modified = chrono::NaiveDate::from_ymd_opt(2024, 3, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 20)
+            .unwrap();
+        let mut zip = ZipWriter::new(Vec::new(), modified);
+        zip.add("system.txt", b"hello").unwrap();
+        zip.add("logs/backend.log", b"line 1\nline 2\n").unwrap();
+        let data = zip.finish().unwrap();
+
+        // Local headers, each followed by its name and stored data.
+        assert_eq!(u32_at(&data, 0), 0x0403_4b50);
+        assert_eq!(u16_at(&data, 8), 0); // stored
+        assert_eq!(u16_at(&data, 10), (10 << 11) | (30 << 5) | 10);
+        assert_eq!(u16_at(&data, 12), (44 << 9) | (3 << 5) | 15);
+        assert_eq!(u32_at(&data, 14), crc32(b"hello"));
+        assert_eq!(u32_at(&data, 18), 5);
+        assert_eq!(u32_at(&data, 22), 5);
+        assert_eq!(u16_at(&data, 26), 10);
+        assert_eq!(&data[30..40], b"system.txt");
+        assert_eq!(&data[40..45], b"hello");
+        let second = 45;
+        assert_eq!(u32_at(&data, second), 0x0403_4b50);
+        assert_eq!(u16_at(&data, second + 26), 16);
+        assert_eq!(&data[second + 30..second + 46], b"logs/backend.log");
+        assert_eq!(&data[second + 46..second + 60], b"line 1\nline 2\n");
+        let central = second + 60;
+
+        // End of central directory: both counts, size and offset.
+        let end = data.len() - 22;
+        assert_eq!(u32_at(&data, end), 0x0605_4b50);
+        assert_eq!(u16_at(&data, end + 8), 2);
+        assert_eq!(u16_at(&data, end + 10), 2);
+        assert_eq!(u32_at(&data, end + 12) as usize, end - central);
+        assert_eq!(u32_at(&data, end + 16) as usize, central);
+        assert_eq!(u16_at(&data, end + 20), 0);
+
+        // Central directory entries point back at the local headers.
+        let mut at = central;
+        for (name, offset, size) in [("system.txt", 0, 5), ("logs/backend.log", second, 14)] {
+            assert_eq!(u32_at(&data, at), 0x0201_4b50);
+            assert_eq!(u32_at(&data, at + 20), size);
+            assert_eq!(u16_at(&data, at + 28) as usize, name.len());
+            assert_eq!(u32_at(&data, at + 42) as usize, offset);
+            assert_eq!(&data[at + 46..at + 46 + name.len()], name.as_bytes());
+            at += 46 + name.len();
+        }
+        assert_eq!(at, end);
+    }
+}
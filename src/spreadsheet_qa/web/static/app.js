@@ -5238,3 +5238,11 @@ document.addEventListener('keydown', (e) => {
     redoFix();
   }
 });
+
+// Shell natif (Tauri) : confirmer que la webview joint elle-même le serveur
+// local, le shell affiche sinon une aide proxy/pare-feu.
+if (globalThis.__TAURI__?.core) {
+  fetch('/health', { cache: 'no-store' })
+    .then((resp) => (resp.ok ? globalThis.__TAURI__.core.invoke('frontend_can_reach') : null))
+    .catch(() => {});
+}